@@ -0,0 +1,155 @@
+use std::fmt;
+use std::iter::Sum;
+use std::ops::{Add, AddAssign};
+
+/// Line, word, character and byte totals, as reported by `rwc`. They are counted the way GNU `wc`
+/// counts them in a UTF-8 locale.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Counts {
+    /// Newline bytes, so a last line without one isn't counted.
+    pub lines: usize,
+    /// Runs of printable characters between whitespace.
+    pub words: usize,
+    /// Unicode scalar values. Bytes that aren't valid UTF-8 aren't characters.
+    pub chars: usize,
+    pub bytes: usize,
+}
+
+impl Counts {
+    /// Counts a buffer of input: one line or many, each with its trailing newline if it has one.
+    /// No word or character can span a newline, so buffers that end at one can be counted
+    /// independently and added up. Splitting anywhere else may miscount.
+    pub fn from_bytes(bytes: &[u8]) -> Counts {
+        let mut counts = Counts {
+            lines: bytes.iter().filter(|&&byte| byte == b'\n').count(),
+            bytes: bytes.len(),
+            ..Counts::default()
+        };
+        let mut in_word = false;
+        for chunk in bytes.utf8_chunks() {
+            for c in chunk.valid().chars() {
+                counts.chars += 1;
+                if c.is_whitespace() {
+                    in_word = false;
+                } else if !in_word && !c.is_control() {
+                    // Like wc, only a printable character starts a word; anything else neither
+                    // starts nor ends one
+                    in_word = true;
+                    counts.words += 1;
+                }
+            }
+        }
+        counts
+    }
+}
+
+impl Add for Counts {
+    type Output = Counts;
+
+    fn add(self, other: Counts) -> Counts {
+        Counts {
+            lines: self.lines + other.lines,
+            words: self.words + other.words,
+            chars: self.chars + other.chars,
+            bytes: self.bytes + other.bytes,
+        }
+    }
+}
+
+impl AddAssign for Counts {
+    fn add_assign(&mut self, other: Counts) {
+        *self = *self + other;
+    }
+}
+
+impl Sum for Counts {
+    fn sum<I: Iterator<Item = Counts>>(iter: I) -> Counts {
+        iter.fold(Counts::default(), Add::add)
+    }
+}
+
+impl fmt::Display for Counts {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "line: {} word: {} char: {} byte: {}",
+            self.lines, self.words, self.chars, self.bytes
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_from_bytes() {
+        assert_eq!(
+            Counts::from_bytes(b"hello there  world\n"),
+            Counts {
+                lines: 1,
+                words: 3,
+                chars: 19,
+                bytes: 19,
+            }
+        );
+        assert_eq!(
+            Counts::from_bytes(b"no newline"),
+            Counts {
+                lines: 0,
+                words: 2,
+                chars: 10,
+                bytes: 10,
+            }
+        );
+    }
+
+    #[test]
+    fn test_from_bytes_many_lines() {
+        assert_eq!(
+            Counts::from_bytes(b"one two\nthree\n\nfour"),
+            Counts::from_bytes(b"one two\n")
+                + Counts::from_bytes(b"three\n")
+                + Counts::from_bytes(b"\n")
+                + Counts::from_bytes(b"four")
+        );
+    }
+
+    #[test]
+    fn test_from_bytes_unicode() {
+        // Multi-byte characters are one character each, a no-break space separates words, and
+        // invalid bytes and control characters are neither characters of a word nor separators
+        assert_eq!(
+            Counts::from_bytes("h\u{e9}llo w\u{f6}rld\u{a0}x\n".as_bytes()),
+            Counts {
+                lines: 1,
+                words: 3,
+                chars: 14,
+                bytes: 17,
+            }
+        );
+        assert_eq!(
+            Counts::from_bytes(b"\xff\xfe \x07a\x07b\n"),
+            Counts {
+                lines: 1,
+                words: 1,
+                chars: 6,
+                bytes: 8,
+            }
+        );
+    }
+
+    #[test]
+    fn test_add() {
+        let mut total = Counts::default();
+        total += Counts::from_bytes(b"a b\n");
+        total += Counts::from_bytes(b"cd");
+        assert_eq!(total.to_string(), "line: 1 word: 3 char: 6 byte: 6");
+        assert_eq!(
+            vec![Counts::from_bytes(b"a b\n"), Counts::from_bytes(b"cd")]
+                .into_iter()
+                .sum::<Counts>(),
+            total
+        );
+    }
+}
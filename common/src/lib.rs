@@ -0,0 +1,8 @@
+//! Small pieces shared by the crates in this workspace, so they stop being copy-pasted between
+//! assignments.
+
+pub mod counts;
+pub mod test_utils;
+pub mod timing;
+
+pub use crate::counts::Counts;
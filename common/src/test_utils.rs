@@ -0,0 +1,68 @@
+//! Helpers for tests. These live in the normal library (rather than behind `cfg(test)`) so that
+//! other crates can use them from their own tests.
+
+use std::cell::Cell;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::{env, fs, process};
+
+/// Counts drops into a shared cell, so tests can catch leaks (too few drops) and double frees
+/// (too many).
+pub struct DropCounter<'a>(pub &'a Cell<usize>);
+
+impl Drop for DropCounter<'_> {
+    fn drop(&mut self) {
+        self.0.set(self.0.get() + 1);
+    }
+}
+
+/// A file in the system temp directory that is deleted again when this is dropped.
+pub struct TempFile {
+    path: PathBuf,
+}
+
+impl TempFile {
+    /// Creates a new temporary file holding `contents`. Panics if the file can't be written.
+    pub fn with_contents(contents: impl AsRef<[u8]>) -> TempFile {
+        static NEXT_ID: AtomicUsize = AtomicUsize::new(0);
+        let path = env::temp_dir().join(format!(
+            "cs110l-test-{}-{}",
+            process::id(),
+            NEXT_ID.fetch_add(1, Ordering::Relaxed)
+        ));
+        fs::write(&path, contents)
+            .unwrap_or_else(|err| panic!("failed to write {}: {}", path.display(), err));
+        TempFile { path }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl Drop for TempFile {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_drop_counter() {
+        let drops = Cell::new(0);
+        drop(vec![DropCounter(&drops), DropCounter(&drops)]);
+        assert_eq!(drops.get(), 2);
+    }
+
+    #[test]
+    fn test_temp_file() {
+        let file = TempFile::with_contents("hello\n");
+        let path = file.path().to_path_buf();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "hello\n");
+        drop(file);
+        assert!(!path.exists());
+    }
+}
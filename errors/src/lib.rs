@@ -0,0 +1,141 @@
+//! The error type shared by the command line tools in this workspace (`deet` and `rwc`), so they
+//! report failures the same way and exit with a status that says what kind of failure it was.
+
+use std::io;
+use std::process;
+use thiserror::Error;
+
+/// A catch-all error type for sources we only need to display.
+pub type BoxError = Box<dyn std::error::Error + Send + Sync>;
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+#[derive(Debug, Error)]
+pub enum Error {
+    /// An I/O operation failed. `context` says what we were doing at the time.
+    #[error("{context}: {source}")]
+    Io {
+        context: String,
+        #[source]
+        source: io::Error,
+    },
+
+    /// Some input (a command argument, a line of a file) was not in the expected format.
+    #[error("{0}")]
+    Parse(String),
+
+    /// A binary's debugging symbols could not be loaded.
+    #[error("could not load debugging symbols from {target}: {message}")]
+    Dwarf { target: String, message: String },
+
+    /// A ptrace (or wait) call on a traced process failed.
+    #[error("{context}: {source}")]
+    Ptrace {
+        context: String,
+        #[source]
+        source: BoxError,
+    },
+
+    /// An operation needed a running process (e.g. deet's inferior), but there isn't one.
+    #[error("the target is not running")]
+    NotRunning,
+
+    /// The program was invoked incorrectly. Holds the usage message.
+    #[error("{0}")]
+    Usage(String),
+}
+
+impl Error {
+    pub fn io(context: impl Into<String>, source: io::Error) -> Error {
+        Error::Io {
+            context: context.into(),
+            source,
+        }
+    }
+
+    pub fn ptrace(context: impl Into<String>, source: impl Into<BoxError>) -> Error {
+        Error::Ptrace {
+            context: context.into(),
+            source: source.into(),
+        }
+    }
+
+    /// The status a tool should exit with when it gives up because of this error. The values are
+    /// the ones from BSD's sysexits.h.
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            Error::Usage(_) => 64,                       // EX_USAGE
+            Error::Parse(_) | Error::Dwarf { .. } => 65, // EX_DATAERR
+            Error::NotRunning => 70,                     // EX_SOFTWARE
+            Error::Ptrace { .. } => 71,                  // EX_OSERR
+            Error::Io { .. } => 74,                      // EX_IOERR
+        }
+    }
+
+    /// Prints the error to stderr and exits with `exit_code()`. Meant for the point in `main`
+    /// where the tool can't do anything useful after the error.
+    pub fn exit(&self) -> ! {
+        eprintln!("{}", self);
+        process::exit(self.exit_code());
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_display() {
+        let err = Error::io(
+            "fail to open foo.txt",
+            io::Error::new(io::ErrorKind::NotFound, "No such file or directory"),
+        );
+        assert_eq!(
+            err.to_string(),
+            "fail to open foo.txt: No such file or directory"
+        );
+
+        let err = Error::Dwarf {
+            target: "samples/segfault".to_string(),
+            message: "unexpected EOF".to_string(),
+        };
+        assert_eq!(
+            err.to_string(),
+            "could not load debugging symbols from samples/segfault: unexpected EOF"
+        );
+
+        let err = Error::ptrace("can not read registers", "ESRCH: No such process");
+        assert_eq!(
+            err.to_string(),
+            "can not read registers: ESRCH: No such process"
+        );
+
+        assert_eq!(
+            Error::Usage("Usage: rwc <file>".to_string()).to_string(),
+            "Usage: rwc <file>"
+        );
+    }
+
+    #[test]
+    fn test_exit_code() {
+        let io_err = Error::io("read", io::Error::from(io::ErrorKind::Other));
+        assert_eq!(io_err.exit_code(), 74);
+        assert_eq!(Error::Parse(String::new()).exit_code(), 65);
+        assert_eq!(Error::NotRunning.exit_code(), 70);
+        assert_eq!(Error::ptrace("step", "failed").exit_code(), 71);
+        assert_eq!(Error::Usage(String::new()).exit_code(), 64);
+    }
+
+    #[test]
+    fn test_source() {
+        use std::error::Error as _;
+
+        let err = Error::io("read", io::Error::from(io::ErrorKind::UnexpectedEof));
+        let source = err.source().expect("Io errors should expose their source");
+        assert_eq!(
+            source.downcast_ref::<io::Error>().unwrap().kind(),
+            io::ErrorKind::UnexpectedEof
+        );
+        assert!(Error::Parse(String::new()).source().is_none());
+    }
+}
@@ -0,0 +1,208 @@
+//! The bodies of the fuzz targets. They live here rather than in `fuzz_targets/` so that
+//! `tests/regressions.rs` can replay saved crash inputs through exactly the same code.
+//!
+//! Each function takes the raw fuzzer input and panics if it finds a bug.
+
+use arbitrary::{Arbitrary, Unstructured};
+use common::Counts;
+use deet::debugger_command::DebuggerCommand;
+use deet_core::{Condition, LocationSpec, Scope};
+use linked_list::LinkedList;
+use std::collections::VecDeque;
+use std::io::BufReader;
+
+/// Feeds every line of the input to deet's command parser, split into tokens the way the REPL
+/// splits them.
+pub fn deet_command(data: &[u8]) {
+    let input = String::from_utf8_lossy(data);
+    for line in input.lines() {
+        let tokens: Vec<&str> = line.split_whitespace().collect();
+        let _ = DebuggerCommand::from_tokens(&tokens);
+    }
+}
+
+/// Parses the input as a location and checks that displaying a parsed location gives back a
+/// string that parses to the same thing.
+pub fn deet_location(data: &[u8]) {
+    let input = match std::str::from_utf8(data) {
+        Ok(input) => input,
+        Err(_) => return,
+    };
+    if let Ok(location) = input.parse::<LocationSpec>() {
+        let shown = location.to_string();
+        assert_eq!(
+            shown.parse::<LocationSpec>().ok(),
+            Some(location),
+            "{:?} did not round trip",
+            shown
+        );
+    }
+}
+
+/// Looks every variable and register up as the length of its name, so conditions get evaluated
+/// all the way through.
+struct NameLength;
+
+impl Scope for NameLength {
+    fn variable(&self, name: &str) -> errors::Result<i64> {
+        Ok(name.len() as i64)
+    }
+
+    fn register(&self, name: &str) -> errors::Result<i64> {
+        Ok(name.len() as i64)
+    }
+}
+
+/// Parses the input as a breakpoint condition, evaluates it, and checks that it round trips
+/// through `Display`.
+pub fn deet_condition(data: &[u8]) {
+    let input = match std::str::from_utf8(data) {
+        Ok(input) => input,
+        Err(_) => return,
+    };
+    if let Ok(condition) = input.parse::<Condition>() {
+        let _ = condition.evaluate(&NameLength);
+        assert_eq!(
+            condition.to_string().parse::<Condition>().ok(),
+            Some(condition)
+        );
+    }
+}
+
+/// Counts arbitrary bytes with rwc, through a reader whose buffer size comes from the first
+/// byte, and checks the result against counting the whole input in one go.
+pub fn rwc_count(data: &[u8]) {
+    let (capacity, rest) = match data.split_first() {
+        Some((&first, rest)) => (first as usize + 1, rest),
+        None => (1, data),
+    };
+    let counted = rwc::count_reader("input", BufReader::with_capacity(capacity, rest));
+    assert_eq!(counted.unwrap(), Counts::from_bytes(rest));
+}
+
+#[derive(Arbitrary, Debug)]
+enum Op {
+    PushFront(u8),
+    PushBack(u8),
+    PopFront,
+    PopBack,
+    Insert(usize, u8),
+    Remove(usize),
+    Swap(usize, usize),
+    Truncate(usize),
+    RotateLeft(usize),
+    Retain(u8),
+    Dedup,
+    Reverse,
+    Sort,
+    Clear,
+    // Moves everything from the index onwards into the second list.
+    SplitOff(usize),
+    // Moves the second list onto the end of the first.
+    Append,
+    // Replaces the second list with a clone of the first.
+    Clone,
+}
+
+fn assert_matches(list: &LinkedList<u8>, model: &VecDeque<u8>) {
+    list.debug_validate();
+    assert_eq!(list.get_size(), model.len());
+    assert_eq!(list.peek_front(), model.front());
+    assert_eq!(list.peek_back(), model.back());
+    assert!(list.iter().eq(model.iter()));
+    assert!(list.iter().rev().eq(model.iter().rev()));
+}
+
+/// Runs a random sequence of operations against a `LinkedList` and a `VecDeque` and checks they
+/// always agree. Indices are taken modulo the length so the operations don't panic by design.
+pub fn linked_list_ops(data: &[u8]) {
+    let mut u = Unstructured::new(data);
+    let ops: Vec<Op> = match u.arbitrary() {
+        Ok(ops) => ops,
+        Err(_) => return,
+    };
+
+    let mut list = LinkedList::new();
+    let mut model = VecDeque::new();
+    let mut other = LinkedList::new();
+    let mut other_model = VecDeque::new();
+    for op in ops {
+        let len = model.len();
+        match op {
+            Op::PushFront(v) => {
+                list.push_front(v);
+                model.push_front(v);
+            }
+            Op::PushBack(v) => {
+                list.push_back(v);
+                model.push_back(v);
+            }
+            Op::PopFront => assert_eq!(list.pop_front(), model.pop_front()),
+            Op::PopBack => assert_eq!(list.pop_back(), model.pop_back()),
+            Op::Insert(i, v) => {
+                let i = i % (len + 1);
+                list.insert(i, v);
+                model.insert(i, v);
+            }
+            Op::Remove(i) => {
+                // Out-of-bounds indices are allowed here and return None.
+                let i = i % (len + 2);
+                assert_eq!(list.remove(i), model.remove(i));
+            }
+            Op::Swap(i, j) => {
+                if len > 0 {
+                    list.swap(i % len, j % len);
+                    model.swap(i % len, j % len);
+                }
+            }
+            Op::Truncate(n) => {
+                let n = n % (len + 2);
+                list.truncate(n);
+                model.truncate(n);
+            }
+            Op::RotateLeft(n) => {
+                let n = n % (len + 1);
+                list.rotate_left(n);
+                model.rotate_left(n);
+            }
+            Op::Retain(modulus) => {
+                let modulus = modulus.max(1);
+                list.retain(|v| v % modulus != 0);
+                model.retain(|v| v % modulus != 0);
+            }
+            Op::Dedup => {
+                list.dedup();
+                let mut deduped: Vec<u8> = model.into_iter().collect();
+                deduped.dedup();
+                model = deduped.into_iter().collect();
+            }
+            Op::Reverse => {
+                list.reverse();
+                model = model.into_iter().rev().collect();
+            }
+            Op::Sort => {
+                list.sort();
+                model.make_contiguous().sort();
+            }
+            Op::Clear => {
+                list.clear();
+                model.clear();
+            }
+            Op::SplitOff(at) => {
+                let at = at % (len + 1);
+                other = list.split_off(at);
+                other_model = model.split_off(at);
+            }
+            Op::Append => {
+                list.append(&mut other);
+                model.append(&mut other_model);
+            }
+            Op::Clone => {
+                other = list.clone();
+                other_model = model.clone();
+            }
+        }
+        assert_matches(&list, &model);
+        assert_matches(&other, &other_model);
+    }
+}
@@ -0,0 +1,50 @@
+//! Replays every input saved under `regressions/<target>/` through its fuzz target, so a crash
+//! the fuzzer found keeps being checked once it is fixed.
+
+use std::fs;
+use std::panic;
+use std::path::Path;
+
+fn replay(target: &str, run: fn(&[u8])) {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("regressions")
+        .join(target);
+    let entries = match fs::read_dir(&dir) {
+        Ok(entries) => entries,
+        Err(_) => return,
+    };
+    let mut failed = Vec::new();
+    for entry in entries {
+        let path = entry.unwrap().path();
+        let data = fs::read(&path).unwrap();
+        if panic::catch_unwind(|| run(&data)).is_err() {
+            failed.push(path.display().to_string());
+        }
+    }
+    assert!(failed.is_empty(), "{} failed on {:?}", target, failed);
+}
+
+#[test]
+fn deet_command() {
+    replay("deet_command", cs110l_fuzz::deet_command);
+}
+
+#[test]
+fn deet_location() {
+    replay("deet_location", cs110l_fuzz::deet_location);
+}
+
+#[test]
+fn deet_condition() {
+    replay("deet_condition", cs110l_fuzz::deet_condition);
+}
+
+#[test]
+fn rwc_count() {
+    replay("rwc_count", cs110l_fuzz::rwc_count);
+}
+
+#[test]
+fn linked_list_ops() {
+    replay("linked_list_ops", cs110l_fuzz::linked_list_ops);
+}
@@ -0,0 +1,50 @@
+use crate::condition::Condition;
+use crate::location::LocationSpec;
+
+/// A breakpoint, as set with `Session::set_breakpoint`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Breakpoint {
+    /// The number the user refers to the breakpoint by. Ids aren't reused after a delete.
+    pub id: usize,
+    /// Where the user asked for the breakpoint.
+    pub location: LocationSpec,
+    /// The address `location` resolved to.
+    pub addr: usize,
+    /// Disabled breakpoints are kept, but not installed in the inferior.
+    pub enabled: bool,
+    /// The breakpoint only stops the inferior when this holds.
+    pub condition: Option<Condition>,
+    /// How many more times the breakpoint lets the inferior carry on instead of stopping it.
+    pub ignore_count: usize,
+    /// How many times the inferior has reached the breakpoint with its condition holding (ignored
+    /// or not) since it was launched.
+    pub hit_count: usize,
+    /// The instruction byte the trap replaced, while the breakpoint is installed in the running
+    /// inferior.
+    pub(crate) orig_byte: Option<u8>,
+}
+
+impl Breakpoint {
+    pub(crate) fn new(id: usize, location: LocationSpec, addr: usize) -> Breakpoint {
+        Breakpoint {
+            id,
+            location,
+            addr,
+            enabled: true,
+            condition: None,
+            ignore_count: 0,
+            hit_count: 0,
+            orig_byte: None,
+        }
+    }
+
+    /// The instruction byte the breakpoint's trap replaced, if it is installed in the running
+    /// inferior.
+    pub fn original_byte(&self) -> Option<u8> {
+        self.orig_byte
+    }
+
+    pub fn is_installed(&self) -> bool {
+        self.orig_byte.is_some()
+    }
+}
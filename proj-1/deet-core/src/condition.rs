@@ -0,0 +1,376 @@
+use errors::Error;
+use std::fmt;
+use std::str::FromStr;
+
+/// A breakpoint condition, as in `break func2 if sum > 40`: a C-like integer expression over
+/// variables (looked up like `Session::read_variable` does), registers (`$rax`), and decimal,
+/// hex and character literals. The breakpoint only stops the inferior when it is non-zero.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Condition {
+    source: String,
+    expr: Expr,
+}
+
+/// Where a condition's variables and registers get their values.
+pub trait Scope {
+    fn variable(&self, name: &str) -> errors::Result<i64>;
+    fn register(&self, name: &str) -> errors::Result<i64>;
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Expr {
+    Number(i64),
+    Variable(String),
+    Register(String),
+    Not(Box<Expr>),
+    Negate(Box<Expr>),
+    Binary(BinaryOp, Box<Expr>, Box<Expr>),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum BinaryOp {
+    Or,
+    And,
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Rem,
+}
+
+impl BinaryOp {
+    fn from_token(token: &str) -> Option<BinaryOp> {
+        Some(match token {
+            "||" => BinaryOp::Or,
+            "&&" => BinaryOp::And,
+            "==" => BinaryOp::Eq,
+            "!=" => BinaryOp::Ne,
+            "<" => BinaryOp::Lt,
+            "<=" => BinaryOp::Le,
+            ">" => BinaryOp::Gt,
+            ">=" => BinaryOp::Ge,
+            "+" => BinaryOp::Add,
+            "-" => BinaryOp::Sub,
+            "*" => BinaryOp::Mul,
+            "/" => BinaryOp::Div,
+            "%" => BinaryOp::Rem,
+            _ => return None,
+        })
+    }
+
+    /// C's precedence; higher binds tighter.
+    fn precedence(self) -> u8 {
+        match self {
+            BinaryOp::Or => 1,
+            BinaryOp::And => 2,
+            BinaryOp::Eq | BinaryOp::Ne => 3,
+            BinaryOp::Lt | BinaryOp::Le | BinaryOp::Gt | BinaryOp::Ge => 4,
+            BinaryOp::Add | BinaryOp::Sub => 5,
+            BinaryOp::Mul | BinaryOp::Div | BinaryOp::Rem => 6,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Number(i64),
+    Identifier(String),
+    Register(String),
+    Operator(&'static str),
+    Open,
+    Close,
+}
+
+/// Longest first, so `<=` isn't read as `<` followed by `=`.
+const OPERATORS: [&str; 14] = [
+    "||", "&&", "==", "!=", "<=", ">=", "<", ">", "+", "-", "*", "/", "%", "!",
+];
+
+/// How deeply parentheses and unary operators can nest, so that parsing (and evaluating) a
+/// hostile condition can't overflow the stack.
+const MAX_DEPTH: usize = 64;
+
+fn tokenize(s: &str) -> Result<Vec<Token>, String> {
+    let mut tokens = Vec::new();
+    let mut rest = s.trim_start();
+    while !rest.is_empty() {
+        let word_len = rest
+            .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+            .unwrap_or(rest.len());
+        if rest.starts_with(|c: char| c.is_ascii_digit()) {
+            let word = &rest[..word_len];
+            let number = match word.strip_prefix("0x").or_else(|| word.strip_prefix("0X")) {
+                Some(hex) => i64::from_str_radix(hex, 16),
+                None => word.parse(),
+            };
+            // from_str_radix would accept a sign, but the word can't contain one
+            tokens.push(Token::Number(
+                number.map_err(|_| format!("invalid number {}", word))?,
+            ));
+            rest = &rest[word_len..];
+        } else if word_len > 0 {
+            tokens.push(Token::Identifier(rest[..word_len].to_string()));
+            rest = &rest[word_len..];
+        } else if let Some(register) = rest.strip_prefix('$') {
+            let len = register
+                .find(|c: char| !c.is_ascii_alphanumeric())
+                .unwrap_or(register.len());
+            if len == 0 {
+                return Err("expected a register name after $".to_string());
+            }
+            tokens.push(Token::Register(register[..len].to_string()));
+            rest = &register[len..];
+        } else if let Some(quoted) = rest.strip_prefix('\'') {
+            let mut chars = quoted.chars();
+            match (chars.next(), chars.next()) {
+                (Some(c), Some('\'')) if c.is_ascii() && c != '\\' => {
+                    tokens.push(Token::Number(c as i64));
+                    rest = chars.as_str();
+                }
+                _ => return Err("invalid character literal".to_string()),
+            }
+        } else if let Some(after) = rest.strip_prefix('(') {
+            tokens.push(Token::Open);
+            rest = after;
+        } else if let Some(after) = rest.strip_prefix(')') {
+            tokens.push(Token::Close);
+            rest = after;
+        } else if let Some(op) = OPERATORS.iter().find(|op| rest.starts_with(*op)) {
+            tokens.push(Token::Operator(op));
+            rest = &rest[op.len()..];
+        } else {
+            return Err(format!("unexpected {:?}", rest.chars().next().unwrap()));
+        }
+        rest = rest.trim_start();
+    }
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+    depth: usize,
+}
+
+impl Parser {
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+
+    /// Parses operands joined by binary operators at least as tight as `min_precedence`.
+    fn binary(&mut self, min_precedence: u8) -> Result<Expr, String> {
+        let mut lhs = self.unary()?;
+        while let Some(Token::Operator(token)) = self.tokens.get(self.pos) {
+            let op = match BinaryOp::from_token(token) {
+                Some(op) if op.precedence() >= min_precedence => op,
+                _ => break,
+            };
+            self.pos += 1;
+            // All the operators are left-associative
+            let rhs = self.binary(op.precedence() + 1)?;
+            lhs = Expr::Binary(op, Box::new(lhs), Box::new(rhs));
+        }
+        Ok(lhs)
+    }
+
+    fn unary(&mut self) -> Result<Expr, String> {
+        self.depth += 1;
+        if self.depth > MAX_DEPTH {
+            return Err("too deeply nested".to_string());
+        }
+        let expr = match self.next() {
+            Some(Token::Number(n)) => Expr::Number(n),
+            Some(Token::Identifier(name)) => Expr::Variable(name),
+            Some(Token::Register(name)) => Expr::Register(name),
+            Some(Token::Operator("!")) => Expr::Not(Box::new(self.unary()?)),
+            Some(Token::Operator("-")) => Expr::Negate(Box::new(self.unary()?)),
+            Some(Token::Open) => {
+                let expr = self.binary(0)?;
+                if self.next() != Some(Token::Close) {
+                    return Err("expected )".to_string());
+                }
+                expr
+            }
+            Some(token) => return Err(format!("unexpected {:?}", token)),
+            None => return Err("unexpected end".to_string()),
+        };
+        self.depth -= 1;
+        Ok(expr)
+    }
+}
+
+impl FromStr for Condition {
+    type Err = Error;
+
+    fn from_str(s: &str) -> errors::Result<Condition> {
+        let invalid =
+            |message: String| Error::Parse(format!("invalid condition {}: {}", s, message));
+        let mut parser = Parser {
+            tokens: tokenize(s).map_err(invalid)?,
+            pos: 0,
+            depth: 0,
+        };
+        let expr = parser.binary(0).map_err(invalid)?;
+        if parser.pos < parser.tokens.len() {
+            return Err(invalid(format!(
+                "unexpected {:?}",
+                parser.tokens[parser.pos]
+            )));
+        }
+        Ok(Condition {
+            source: s.trim().to_string(),
+            expr,
+        })
+    }
+}
+
+impl fmt::Display for Condition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.source)
+    }
+}
+
+impl Condition {
+    /// Evaluates the condition. Arithmetic wraps around like it does in the inferior.
+    pub fn evaluate(&self, scope: &dyn Scope) -> errors::Result<bool> {
+        Ok(self.expr.evaluate(scope)? != 0)
+    }
+}
+
+impl Expr {
+    fn evaluate(&self, scope: &dyn Scope) -> errors::Result<i64> {
+        let (op, lhs, rhs) = match self {
+            Expr::Number(n) => return Ok(*n),
+            Expr::Variable(name) => return scope.variable(name),
+            Expr::Register(name) => return scope.register(name),
+            Expr::Not(expr) => return Ok((expr.evaluate(scope)? == 0) as i64),
+            Expr::Negate(expr) => return Ok(expr.evaluate(scope)?.wrapping_neg()),
+            Expr::Binary(op, lhs, rhs) => (op, lhs.evaluate(scope)?, rhs),
+        };
+        // && and || don't evaluate their right-hand side unless they need to, as in C
+        match op {
+            BinaryOp::And if lhs == 0 => return Ok(0),
+            BinaryOp::Or if lhs != 0 => return Ok(1),
+            _ => {}
+        }
+        let rhs = rhs.evaluate(scope)?;
+        Ok(match op {
+            BinaryOp::Or | BinaryOp::And => (rhs != 0) as i64,
+            BinaryOp::Eq => (lhs == rhs) as i64,
+            BinaryOp::Ne => (lhs != rhs) as i64,
+            BinaryOp::Lt => (lhs < rhs) as i64,
+            BinaryOp::Le => (lhs <= rhs) as i64,
+            BinaryOp::Gt => (lhs > rhs) as i64,
+            BinaryOp::Ge => (lhs >= rhs) as i64,
+            BinaryOp::Add => lhs.wrapping_add(rhs),
+            BinaryOp::Sub => lhs.wrapping_sub(rhs),
+            BinaryOp::Mul => lhs.wrapping_mul(rhs),
+            BinaryOp::Div | BinaryOp::Rem if rhs == 0 => {
+                return Err(Error::Parse("division by zero".to_string()))
+            }
+            BinaryOp::Div => lhs.wrapping_div(rhs),
+            BinaryOp::Rem => lhs.wrapping_rem(rhs),
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    struct TestScope;
+
+    impl Scope for TestScope {
+        fn variable(&self, name: &str) -> errors::Result<i64> {
+            match name {
+                "x" => Ok(7),
+                "y" => Ok(-3),
+                _ => Err(Error::Parse(format!("no variable {}", name))),
+            }
+        }
+
+        fn register(&self, name: &str) -> errors::Result<i64> {
+            match name {
+                "rax" => Ok(0x10),
+                _ => Err(Error::Parse(format!("no register {}", name))),
+            }
+        }
+    }
+
+    fn eval(s: &str) -> errors::Result<bool> {
+        s.parse::<Condition>()?.evaluate(&TestScope)
+    }
+
+    #[test]
+    fn test_evaluate() {
+        for s in &[
+            "x > 5",
+            "x == 7 && y < 0",
+            "x < 0 || y == -3",
+            "x + y * 2 == 1",
+            "(x + y) * 2 == 8",
+            "10 - 4 - 3 == 3",
+            "x % 4 == 3 && x / 2 == 3",
+            "!(x <= 6)",
+            "-y == 3",
+            "$rax == 0x10",
+            "'x' == 120",
+            "1",
+        ] {
+            assert!(eval(s).unwrap(), "{}", s);
+        }
+        for s in &["x < 5", "x != 7", "0", "!x", "x >= 8 || y > 0"] {
+            assert!(!eval(s).unwrap(), "{}", s);
+        }
+    }
+
+    #[test]
+    fn test_evaluate_errors() {
+        assert!(eval("nope > 1").is_err());
+        assert!(eval("$nope").is_err());
+        assert!(eval("x / (y + 3)").is_err());
+        // The right-hand side of && and || is only evaluated when needed
+        assert!(!eval("x < 0 && nope").unwrap());
+        assert!(eval("x > 0 || nope").unwrap());
+    }
+
+    #[test]
+    fn test_parse_invalid() {
+        let too_deep = format!("{}1{}", "(".repeat(100), ")".repeat(100));
+        for s in &[
+            "",
+            "x >",
+            "x > > 5",
+            "(x",
+            "x)",
+            "x = 5",
+            "x 5",
+            "99999999999999999999",
+            "0xg",
+            "$",
+            "'ab'",
+            "x # 1",
+            &too_deep,
+        ] {
+            assert!(
+                matches!(s.parse::<Condition>(), Err(Error::Parse(_))),
+                "{:?} should not parse",
+                s
+            );
+        }
+    }
+
+    #[test]
+    fn test_display() {
+        let condition: Condition = "  x >  5 ".parse().unwrap();
+        assert_eq!(condition.to_string(), "x >  5");
+    }
+}
@@ -5,8 +5,8 @@ use std::convert::TryInto;
 use std::{fmt, fs};
 
 #[derive(Debug)]
-pub enum Error {
-    ErrorOpeningFile,
+pub(crate) enum Error {
+    ErrorOpeningFile(std::io::Error),
     DwarfFormatError(gimli_wrapper::Error),
 }
 
@@ -28,11 +28,11 @@ impl From<gimli_wrapper::Error> for Error {
 }
 
 impl DwarfData {
-    pub fn from_file(path: &str) -> Result<DwarfData, Error> {
-        let file = fs::File::open(path).or(Err(Error::ErrorOpeningFile))?;
-        let mmap = unsafe { memmap::Mmap::map(&file).or(Err(Error::ErrorOpeningFile))? };
-        let object = object::File::parse(&*mmap)
-            .or_else(|e| Err(gimli_wrapper::Error::ObjectError(e.to_string())))?;
+    pub(crate) fn from_file(path: &str) -> Result<DwarfData, Error> {
+        let file = fs::File::open(path).map_err(Error::ErrorOpeningFile)?;
+        let mmap = unsafe { memmap::Mmap::map(&file).map_err(Error::ErrorOpeningFile)? };
+        let object = object::File::parse(&mmap)
+            .map_err(|e| gimli_wrapper::Error::ObjectError(e.to_string()))?;
         let endian = if object.is_little_endian() {
             gimli::RunTimeEndian::Little
         } else {
@@ -40,22 +40,34 @@ impl DwarfData {
         };
         Ok(DwarfData {
             files: gimli_wrapper::load_file(&object, endian)?,
-            addr2line: Context::new(&object).or_else(|e| Err(gimli_wrapper::Error::from(e)))?,
+            addr2line: Context::new(&object).map_err(gimli_wrapper::Error::from)?,
         })
     }
 
+    /// Finds the source file named `file`, which can be the file's full path or any trailing
+    /// part of it ("function_calls.c", "samples/function_calls.c").
     #[allow(dead_code)]
     fn get_target_file(&self, file: &str) -> Option<&File> {
-        self.files.iter().find(|f| {
-            f.name == file || (!file.contains("/") && f.name.ends_with(&format!("/{}", file)))
-        })
+        self.files
+            .iter()
+            .find(|f| f.name == file || f.name.ends_with(&format!("/{}", file)))
+    }
+
+    /// The full name of the source file `file` refers to (see `get_target_file`), or of the first
+    /// source file if `file` is `None`.
+    pub fn get_source_file(&self, file: Option<&str>) -> Option<&str> {
+        match file {
+            Some(filename) => self.get_target_file(filename),
+            None => self.files.first(),
+        }
+        .map(|file| file.name.as_str())
     }
 
     #[allow(dead_code)]
     pub fn get_addr_for_line(&self, file: Option<&str>, line_number: usize) -> Option<usize> {
         let target_file = match file {
             Some(filename) => self.get_target_file(filename)?,
-            None => self.files.get(0)?,
+            None => self.files.first()?,
         };
         Some(
             target_file
@@ -87,6 +99,30 @@ impl DwarfData {
         }
     }
 
+    /// Returns true if `addr` is where a row of the line table starts, i.e. the first instruction
+    /// generated for a (part of a) source line.
+    pub fn is_line_start(&self, addr: usize) -> bool {
+        self.files
+            .iter()
+            .any(|file| file.lines.iter().any(|line| line.address == addr))
+    }
+
+    /// Finds a global variable by name.
+    pub fn get_global_variable(&self, name: &str) -> Option<&Variable> {
+        self.files
+            .iter()
+            .flat_map(|file| file.global_variables.iter())
+            .find(|var| var.name == name)
+    }
+
+    /// Returns the function whose code contains `addr`.
+    pub fn get_function_containing(&self, addr: usize) -> Option<&Function> {
+        self.files
+            .iter()
+            .flat_map(|file| file.functions.iter())
+            .find(|func| func.address <= addr && addr < func.address + func.text_length)
+    }
+
     #[allow(dead_code)]
     pub fn get_line_from_addr(&self, curr_addr: usize) -> Option<Line> {
         let location = self
@@ -152,17 +188,43 @@ impl DwarfData {
 pub struct Type {
     pub name: String,
     pub size: usize,
+    pub kind: TypeKind,
 }
 
 impl Type {
-    pub fn new(name: String, size: usize) -> Self {
-        Type {
-            name: name,
-            size: size,
-        }
+    pub fn new(name: String, size: usize, kind: TypeKind) -> Self {
+        Type { name, size, kind }
     }
 }
 
+/// How to interpret a value's bytes, as far as deet knows how to show them.
+#[derive(Debug, Clone, Default)]
+pub enum TypeKind {
+    Signed,
+    Unsigned,
+    /// `char`, `signed char` and `unsigned char`, which are shown as characters.
+    Char {
+        signed: bool,
+    },
+    Bool,
+    Float,
+    /// A pointer to the given type, or a `void *`.
+    Pointer(Option<Box<Type>>),
+    /// Structs, arrays and anything else.
+    #[default]
+    Other,
+}
+
+/// Where a function's variables are addressed from (`DW_AT_frame_base`).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FrameBase {
+    /// The canonical frame address: the stack pointer before the call instruction. This is what
+    /// gcc uses.
+    Cfa,
+    /// The value of a register, by DWARF register number (e.g. 6 for %rbp under clang).
+    Register(u16),
+}
+
 #[derive(Clone)]
 pub enum Location {
     Address(usize),
@@ -199,6 +261,7 @@ pub struct Function {
     pub address: usize,
     pub text_length: usize,
     pub line_number: usize, // Line number in source file
+    pub frame_base: Option<FrameBase>,
     pub variables: Vec<Variable>,
 }
 
@@ -222,5 +285,3 @@ impl fmt::Display for Line {
         write!(f, "{}:{}", self.file, self.number)
     }
 }
-
-
@@ -5,12 +5,11 @@
 //!
 //! This code is a huge mess. Please don't read it unless you're trying to do an extension :)
 
-use gimli;
 use gimli::{UnitOffset, UnitSectionOffset};
 use object::Object;
 use std::borrow;
 //use std::io::{BufWriter, Write};
-use crate::dwarf_data::{File, Function, Line, Location, Type, Variable};
+use crate::dwarf_data::{File, FrameBase, Function, Line, Location, Type, TypeKind, Variable};
 use std::collections::HashMap;
 use std::convert::TryInto;
 use std::fmt::Write;
@@ -34,13 +33,14 @@ pub fn load_file(object: &object::File, endian: gimli::RunTimeEndian) -> Result<
     let borrow_section: &dyn for<'a> Fn(
         &'a borrow::Cow<[u8]>,
     ) -> gimli::EndianSlice<'a, gimli::RunTimeEndian> =
-        &|section| gimli::EndianSlice::new(&*section, endian);
+        &|section| gimli::EndianSlice::new(section, endian);
 
     // Create `EndianSlice`s for all of the sections.
     let dwarf = dwarf_cow.borrow(&borrow_section);
 
-    // Define a mapping from type offsets to type structs
-    let mut offset_to_type: HashMap<usize, Type> = HashMap::new();
+    // Define a mapping from type offsets to the type DIEs, resolved into types when a variable
+    // uses them
+    let mut offset_to_type: HashMap<usize, RawType> = HashMap::new();
 
     let mut compilation_units: Vec<File> = Vec::new();
 
@@ -49,6 +49,15 @@ pub fn load_file(object: &object::File, endian: gimli::RunTimeEndian) -> Result<
     while let Some(header) = iter.next()? {
         let unit = dwarf.unit(header)?;
 
+        // Collect the unit's types first, since a variable can refer to a type that comes after
+        // it
+        let mut entries = unit.entries();
+        while let Some((_, entry)) = entries.next_dfs()? {
+            if let Some(raw_type) = get_raw_type(entry, &unit, &dwarf)? {
+                offset_to_type.insert(section_offset(entry.offset(), &unit), raw_type);
+            }
+        }
+
         // Iterate over the Debugging Information Entries (DIEs) in the unit.
         let mut depth = 0;
         let mut entries = unit.entries();
@@ -60,7 +69,8 @@ pub fn load_file(object: &object::File, endian: gimli::RunTimeEndian) -> Result<
                 gimli::DW_TAG_compile_unit => {
                     let name = if let Ok(Some(attr)) = entry.attr(gimli::DW_AT_name) {
                         if let Ok(DebugValue::Str(name)) = get_attr_value(&attr, &unit, &dwarf) {
-                            name
+                            // Use the same absolute path the line program (and addr2line) use
+                            absolute_path(&unit, name.as_ref())?
                         } else {
                             "<unknown>".to_string()
                         }
@@ -74,33 +84,6 @@ pub fn load_file(object: &object::File, endian: gimli::RunTimeEndian) -> Result<
                         lines: Vec::new(),
                     });
                 }
-                gimli::DW_TAG_base_type => {
-                    let name = if let Ok(Some(attr)) = entry.attr(gimli::DW_AT_name) {
-                        if let Ok(DebugValue::Str(name)) = get_attr_value(&attr, &unit, &dwarf) {
-                            name
-                        } else {
-                            "<unknown>".to_string()
-                        }
-                    } else {
-                        "<unknown>".to_string()
-                    };
-                    let byte_size = if let Ok(Some(attr)) = entry.attr(gimli::DW_AT_byte_size) {
-                        if let Ok(DebugValue::Uint(byte_size)) =
-                            get_attr_value(&attr, &unit, &dwarf)
-                        {
-                            byte_size
-                        } else {
-                            // TODO: report error?
-                            0
-                        }
-                    } else {
-                        // TODO: report error?
-                        0
-                    };
-                    let type_offset = entry.offset().0;
-                    offset_to_type
-                        .insert(type_offset, Type::new(name, byte_size.try_into().unwrap()));
-                }
                 gimli::DW_TAG_subprogram => {
                     let mut func: Function = Default::default();
                     let mut attrs = entry.attrs();
@@ -129,6 +112,9 @@ pub fn load_file(object: &object::File, endian: gimli::RunTimeEndian) -> Result<
                                     func.line_number = line_number.try_into().unwrap();
                                 }
                             }
+                            gimli::DW_AT_frame_base => {
+                                func.frame_base = get_frame_base(&attr, &unit);
+                            }
                             _ => {}
                         }
                     }
@@ -151,9 +137,7 @@ pub fn load_file(object: &object::File, endian: gimli::RunTimeEndian) -> Result<
                             }
                             gimli::DW_AT_type => {
                                 if let Ok(DebugValue::Size(offset)) = val {
-                                    if let Some(dtype) = offset_to_type.get(&offset).clone() {
-                                        entity_type = Some(dtype.clone());
-                                    }
+                                    entity_type = Some(resolve_type(&offset_to_type, offset, 0));
                                 }
                             }
                             gimli::DW_AT_location => {
@@ -169,11 +153,11 @@ pub fn load_file(object: &object::File, endian: gimli::RunTimeEndian) -> Result<
                             _ => {}
                         }
                     }
-                    if entity_type.is_some() && location.is_some() {
+                    if let (Some(entity_type), Some(location)) = (entity_type, location) {
                         let var = Variable {
                             name,
-                            entity_type: entity_type.unwrap(),
-                            location: location.unwrap(),
+                            entity_type,
+                            location,
                             line_number: line_number.try_into().unwrap(),
                         };
                         if depth == 1 {
@@ -221,9 +205,8 @@ pub fn load_file(object: &object::File, endian: gimli::RunTimeEndian) -> Result<
                     }
 
                     // Get the File
-                    let file = compilation_units
-                        .iter_mut()
-                        .find(|f| f.name == path.as_os_str().to_str().unwrap());
+                    let path = absolute_path(&unit, &path)?;
+                    let file = compilation_units.iter_mut().find(|f| f.name == path);
 
                     // Determine line/column. DWARF line/column is never 0, so we use that
                     // but other applications may want to display this differently.
@@ -243,16 +226,181 @@ pub fn load_file(object: &object::File, endian: gimli::RunTimeEndian) -> Result<
     Ok(compilation_units)
 }
 
+/// A type DIE, with the types it refers to still as offsets.
+enum RawType {
+    Base {
+        name: String,
+        size: usize,
+        kind: TypeKind,
+    },
+    Pointer {
+        target: Option<usize>,
+    },
+    /// A typedef (with its name), or a `const` or `volatile` qualifier (with the qualifier).
+    Alias {
+        name: Option<String>,
+        qualifier: Option<&'static str>,
+        target: Option<usize>,
+    },
+    /// Structs, unions, arrays and so on, which are only shown by name.
+    Other {
+        name: String,
+        size: usize,
+    },
+}
+
+fn get_raw_type<R: Reader>(
+    entry: &gimli::DebuggingInformationEntry<R>,
+    unit: &gimli::Unit<R>,
+    dwarf: &gimli::Dwarf<R>,
+) -> Result<Option<RawType>, Error> {
+    let name = match entry.attr(gimli::DW_AT_name)? {
+        Some(attr) => match get_attr_value(&attr, unit, dwarf) {
+            Ok(DebugValue::Str(name)) => Some(name),
+            _ => None,
+        },
+        None => None,
+    };
+    let size = match entry.attr_value(gimli::DW_AT_byte_size)? {
+        Some(value) => value.udata_value().unwrap_or(0).try_into().unwrap_or(0),
+        None => 0,
+    };
+    let target = match entry.attr(gimli::DW_AT_type)? {
+        Some(attr) => match get_attr_value(&attr, unit, dwarf) {
+            Ok(DebugValue::Size(offset)) => Some(offset),
+            _ => None,
+        },
+        None => None,
+    };
+    let raw_type = match entry.tag() {
+        gimli::DW_TAG_base_type => {
+            let kind = match entry.attr_value(gimli::DW_AT_encoding)? {
+                Some(gimli::AttributeValue::Encoding(encoding)) => match encoding {
+                    gimli::DW_ATE_signed => TypeKind::Signed,
+                    gimli::DW_ATE_unsigned => TypeKind::Unsigned,
+                    gimli::DW_ATE_signed_char => TypeKind::Char { signed: true },
+                    gimli::DW_ATE_unsigned_char => TypeKind::Char { signed: false },
+                    gimli::DW_ATE_boolean => TypeKind::Bool,
+                    gimli::DW_ATE_float => TypeKind::Float,
+                    _ => TypeKind::Other,
+                },
+                _ => TypeKind::Other,
+            };
+            RawType::Base {
+                name: name.unwrap_or_else(|| "<unknown>".to_string()),
+                size,
+                kind,
+            }
+        }
+        gimli::DW_TAG_pointer_type => RawType::Pointer { target },
+        gimli::DW_TAG_typedef => RawType::Alias {
+            name,
+            qualifier: None,
+            target,
+        },
+        gimli::DW_TAG_const_type => RawType::Alias {
+            name: None,
+            qualifier: Some("const"),
+            target,
+        },
+        gimli::DW_TAG_volatile_type => RawType::Alias {
+            name: None,
+            qualifier: Some("volatile"),
+            target,
+        },
+        gimli::DW_TAG_structure_type
+        | gimli::DW_TAG_union_type
+        | gimli::DW_TAG_enumeration_type
+        | gimli::DW_TAG_array_type => {
+            let prefix = match entry.tag() {
+                gimli::DW_TAG_structure_type => "struct ",
+                gimli::DW_TAG_union_type => "union ",
+                gimli::DW_TAG_enumeration_type => "enum ",
+                _ => "",
+            };
+            RawType::Other {
+                name: format!("{}{}", prefix, name.as_deref().unwrap_or("<anonymous>")),
+                size,
+            }
+        }
+        _ => return Ok(None),
+    };
+    Ok(Some(raw_type))
+}
+
+/// Builds the type at `offset` out of the type DIEs it refers to.
+fn resolve_type(types: &HashMap<usize, RawType>, offset: usize, depth: usize) -> Type {
+    // Nothing in C nests this deep; this only guards against malformed debugging information
+    const MAX_DEPTH: usize = 16;
+    let unknown = || Type::new("<unknown>".to_string(), 0, TypeKind::Other);
+    if depth > MAX_DEPTH {
+        return unknown();
+    }
+    match types.get(&offset) {
+        Some(RawType::Base { name, size, kind }) => Type::new(name.clone(), *size, kind.clone()),
+        Some(RawType::Pointer { target }) => {
+            let target = target.map(|target| resolve_type(types, target, depth + 1));
+            let name = match &target {
+                Some(target) if target.name.ends_with('*') => format!("{}*", target.name),
+                Some(target) => format!("{} *", target.name),
+                None => "void *".to_string(),
+            };
+            Type::new(
+                name,
+                std::mem::size_of::<usize>(),
+                TypeKind::Pointer(target.map(Box::new)),
+            )
+        }
+        Some(RawType::Alias {
+            name,
+            qualifier,
+            target,
+        }) => {
+            let mut resolved = match target {
+                Some(target) => resolve_type(types, *target, depth + 1),
+                None => Type::new("void".to_string(), 0, TypeKind::Other),
+            };
+            if let Some(name) = name {
+                resolved.name = name.clone();
+            } else if let Some(qualifier) = qualifier {
+                resolved.name = format!("{} {}", qualifier, resolved.name);
+            }
+            resolved
+        }
+        Some(RawType::Other { name, size }) => Type::new(name.clone(), *size, TypeKind::Other),
+        None => unknown(),
+    }
+}
+
+fn get_frame_base<R: Reader>(
+    attr: &gimli::Attribute<R>,
+    unit: &gimli::Unit<R>,
+) -> Option<FrameBase> {
+    if let gimli::AttributeValue::Exprloc(ref data) = attr.value() {
+        let mut pc = data.0.clone();
+        match gimli::Operation::parse(&mut pc, unit.encoding()).ok()? {
+            gimli::Operation::CallFrameCFA => return Some(FrameBase::Cfa),
+            gimli::Operation::Register { register } => {
+                return Some(FrameBase::Register(register.0))
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
 #[derive(Debug, Clone)]
 pub enum DebugValue {
     Str(String),
     Uint(u64),
+    #[allow(dead_code)]
     Int(i64),
     Size(usize),
     NoVal,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[allow(clippy::enum_variant_names)]
 pub enum Error {
     GimliError(gimli::Error),
     Addr2lineError(addr2line::gimli::Error),
@@ -313,6 +461,25 @@ fn get_location<R: Reader>(attr: &gimli::Attribute<R>, unit: &gimli::Unit<R>) ->
 }
 
 // based on dwarf_dump.rs
+/// Resolves a path from the debugging information against the unit's compilation directory.
+fn absolute_path<R: Reader>(unit: &gimli::Unit<R>, path: &path::Path) -> Result<String, Error> {
+    let mut full = path::PathBuf::new();
+    if let Some(ref comp_dir) = unit.comp_dir {
+        full.push(comp_dir.to_string_lossy()?.as_ref());
+    }
+    // Pushing an absolute path replaces whatever was there
+    full.push(path);
+    Ok(full.to_string_lossy().into_owned())
+}
+
+/// Turns a unit-relative DIE offset into the section offset types are keyed by.
+fn section_offset<R: Reader>(offset: UnitOffset, unit: &gimli::Unit<R>) -> usize {
+    match offset.to_unit_section_offset(unit) {
+        UnitSectionOffset::DebugInfoOffset(goff) => goff.0,
+        UnitSectionOffset::DebugTypesOffset(goff) => goff.0,
+    }
+}
+
 fn get_attr_value<R: Reader>(
     attr: &gimli::Attribute<R>,
     unit: &gimli::Unit<R>,
@@ -328,14 +495,7 @@ fn get_attr_value<R: Reader>(
             Ok(DebugValue::Str(w.to_string()))
         }
         gimli::AttributeValue::UnitRef(offset) => {
-            match offset.to_unit_section_offset(unit) {
-                UnitSectionOffset::DebugInfoOffset(goff) => {
-                    Ok(DebugValue::Size(goff.0))
-                }
-                UnitSectionOffset::DebugTypesOffset(goff) => {
-                    Ok(DebugValue::Size(goff.0))
-                }
-            }
+            Ok(DebugValue::Size(section_offset(offset, unit)))
         }
         gimli::AttributeValue::DebugStrRef(offset) => {
             if let Ok(s) = dwarf.debug_str.get_str(offset) {
@@ -344,6 +504,16 @@ fn get_attr_value<R: Reader>(
                 Ok(DebugValue::Str(format!("<.debug_str+0x{:08x}>", offset.0)))
             }
         }
+        gimli::AttributeValue::DebugLineStrRef(offset) => {
+            if let Ok(s) = dwarf.debug_line_str.get_str(offset) {
+                Ok(DebugValue::Str(format!("{}", s.to_string_lossy()?)))
+            } else {
+                Ok(DebugValue::Str(format!(
+                    "<.debug_line_str+0x{:08x}>",
+                    offset.0
+                )))
+            }
+        }
         gimli::AttributeValue::Sdata(data) => Ok(DebugValue::Int(data)),
         gimli::AttributeValue::Addr(data) => Ok(DebugValue::Uint(data)),
         gimli::AttributeValue::Udata(data) => Ok(DebugValue::Uint(data)),
@@ -356,9 +526,7 @@ fn get_attr_value<R: Reader>(
             dump_file_index(w, value, unit, dwarf)?;
             Ok(DebugValue::Str(w.to_string()))
         }
-        _ => {
-            Ok(DebugValue::NoVal)
-        }
+        _ => Ok(DebugValue::NoVal),
     }
 }
 
@@ -0,0 +1,634 @@
+use nix::sys::ptrace;
+use nix::sys::signal;
+use nix::sys::wait::{waitpid, WaitPidFlag, WaitStatus};
+use nix::unistd::Pid;
+use std::fs;
+use std::mem::size_of;
+use std::os::unix::process::CommandExt;
+use std::process::Command;
+
+use crate::breakpoint::Breakpoint;
+use crate::dwarf_data::{DwarfData, Line};
+use errors::{BoxError, Error};
+
+/// One frame of a backtrace.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Frame {
+    /// Where execution is in this frame (for callers, the return address).
+    pub rip: usize,
+    /// The function containing `rip`, if debugging information covers it.
+    pub function: Option<String>,
+    /// The source line containing `rip`, if debugging information covers it.
+    pub line: Option<Line>,
+}
+
+pub enum Status {
+    /// Indicates inferior stopped. Contains the signal that stopped the process, as well as the
+    /// current instruction pointer that it is stopped at.
+    Stopped(signal::Signal, usize),
+
+    /// Indicates inferior exited normally. Contains the exit status code.
+    Exited(i32),
+
+    /// Indicates the inferior exited due to a signal. Contains the signal that killed the
+    /// process.
+    Signaled(signal::Signal),
+
+    /// The thread being single-stepped exited, though the process carries on. Contains the
+    /// thread's id. The main thread is selected in its place.
+    ThreadExited(usize),
+}
+
+/// A thread of the inferior, as listed by `Session::threads`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Thread {
+    /// The number deet shows for the thread, counting from 1 in the order threads appeared.
+    pub id: usize,
+    /// The kernel's id for the thread. The main thread's is the process id.
+    pub tid: i32,
+    /// Whether this is the thread that registers are read from and that is stepped.
+    pub selected: bool,
+    /// Where the thread is stopped.
+    pub frame: Frame,
+}
+
+/// The error for a waitpid status that tracing the inferior the way deet does should never
+/// produce (e.g. a ptrace event deet didn't ask for).
+fn unexpected_status(status: WaitStatus) -> BoxError {
+    format!("waitpid returned unexpected status: {:?}", status).into()
+}
+
+/// This function calls ptrace with PTRACE_TRACEME to enable debugging on a process. You should use
+/// pre_exec with Command to call this in the child process.
+fn child_traceme() -> Result<(), std::io::Error> {
+    ptrace::traceme().or(Err(std::io::Error::other("ptrace TRACEME failed")))
+}
+
+fn align_addr_to_word(addr: usize) -> usize {
+    addr & (-(size_of::<usize>() as isize) as usize)
+}
+
+/// The byte of the x86 `int3` instruction, which raises SIGTRAP.
+pub const TRAP_BYTE: u8 = 0xcc;
+
+/// waitpid flags for the inferior's threads. Threads are clone children, which need `__WALL`;
+/// `__WNOTHREAD` keeps us from collecting another debugger thread's inferior (as happens in the
+/// tests, which run sessions side by side).
+const WAIT_FLAGS: WaitPidFlag =
+    WaitPidFlag::from_bits_truncate(WaitPidFlag::__WALL.bits() | WaitPidFlag::__WNOTHREAD.bits());
+
+/// Waits for the SIGSTOP that attaching sends thread `tid`, so it isn't left to stop the process
+/// after we detach. Signals that come first are passed on, except the SIGTRAP of an exec the
+/// thread was in the middle of. Returns false if the thread exited instead.
+fn wait_attached(tid: Pid) -> Result<bool, nix::Error> {
+    loop {
+        match waitpid(tid, Some(WAIT_FLAGS))? {
+            WaitStatus::Stopped(_, signal::Signal::SIGSTOP) => return Ok(true),
+            WaitStatus::Stopped(_, signal::Signal::SIGTRAP) => ptrace::cont(tid, None)?,
+            WaitStatus::Stopped(_, signal) => ptrace::cont(tid, signal)?,
+            _ => return Ok(false),
+        }
+    }
+}
+
+/// What deet knows about one of the inferior's threads.
+struct TracedThread {
+    id: usize,
+    tid: Pid,
+    /// A signal the thread got while deet was stopping it, to pass on when it is resumed.
+    signal: Option<signal::Signal>,
+    /// Whether the thread has yet to report the SIGSTOP deet sent it.
+    stopping: bool,
+    /// Whether deet moved the thread back onto a trap it hit while being stopped, to take it
+    /// again (and have it reported) once resumed.
+    retrap: bool,
+}
+
+/// The traced process. Whenever deet has control every thread is stopped: `cont` runs them all
+/// until one of them stops, and then stops the rest, while single steps only run the selected
+/// thread.
+pub struct Inferior {
+    pid: Pid,
+    /// Whether we attached to the process rather than starting it.
+    attached: bool,
+    /// The process's threads, the main thread (whose tid is `pid`) first.
+    threads: Vec<TracedThread>,
+    next_thread_id: usize,
+    /// The thread that registers are read from and that steps. A stop selects the thread that
+    /// stopped.
+    selected: Pid,
+}
+
+impl Inferior {
+    fn traced(pid: Pid, attached: bool) -> Inferior {
+        let mut inferior = Inferior {
+            pid,
+            attached,
+            threads: Vec::new(),
+            next_thread_id: 1,
+            selected: pid,
+        };
+        inferior.add_thread(pid);
+        inferior
+    }
+
+    /// Attempts to start a new inferior process. The inferior is left stopped before its first
+    /// instruction.
+    pub fn new(target: &str, args: &[String]) -> errors::Result<Inferior> {
+        let mut cmd = Command::new(target);
+        cmd.args(args);
+        unsafe {
+            cmd.pre_exec(child_traceme);
+        }
+        let child = cmd
+            .spawn()
+            .map_err(|err| Error::io(format!("fail to spawn {}", target), err))?;
+        let inferior = Inferior::traced(Pid::from_raw(child.id() as i32), false);
+        let status = inferior
+            .wait_thread(inferior.pid)
+            .map_err(|err| Error::ptrace("failed to stop target programme", err))?;
+        match status {
+            Status::Stopped(signal::Signal::SIGTRAP, _) => {}
+            Status::Exited(exit_code) => {
+                return Err(Error::ptrace(
+                    "failed to start target programme",
+                    format!("exited prematurely (status {})", exit_code),
+                ))
+            }
+            Status::Signaled(signal) | Status::Stopped(signal, _) => {
+                return Err(Error::ptrace(
+                    "failed to start target programme",
+                    format!("unexpected signal {}", signal.as_str()),
+                ))
+            }
+            Status::ThreadExited(_) => unreachable!(),
+        }
+        ptrace::setoptions(inferior.pid, ptrace::Options::PTRACE_O_TRACECLONE)
+            .map_err(|err| Error::ptrace("failed to trace threads of target programme", err))?;
+        Ok(inferior)
+    }
+
+    /// Starts tracing the running process `pid`, which is left stopped wherever it was. Every
+    /// thread is attached to.
+    pub fn attach(pid: Pid) -> errors::Result<Inferior> {
+        let context = || format!("failed to attach to process {}", pid);
+        ptrace::attach(pid).map_err(|err| Error::ptrace(context(), err))?;
+        let mut inferior = Inferior::traced(pid, true);
+        if !wait_attached(pid).map_err(|err| Error::ptrace(context(), err))? {
+            return Err(Error::ptrace(context(), "the process exited"));
+        }
+        // Threads are attached to one by one, and the ones not attached yet can start more, so
+        // keep looking until there are no new ones
+        loop {
+            let tasks = fs::read_dir(format!("/proc/{}/task", pid))
+                .map_err(|err| Error::io(context(), err))?;
+            let new_tids: Vec<Pid> = tasks
+                .filter_map(|task| task.ok()?.file_name().to_str()?.parse().ok())
+                .map(Pid::from_raw)
+                .filter(|&tid| inferior.thread(tid).is_none())
+                .collect();
+            if new_tids.is_empty() {
+                break;
+            }
+            for tid in new_tids {
+                // The thread may have exited since it was listed
+                if ptrace::attach(tid).is_ok() && wait_attached(tid).unwrap_or(false) {
+                    inferior.add_thread(tid);
+                }
+            }
+        }
+        for thread in inferior.threads.iter() {
+            ptrace::setoptions(thread.tid, ptrace::Options::PTRACE_O_TRACECLONE)
+                .map_err(|err| Error::ptrace(context(), err))?;
+        }
+        Ok(inferior)
+    }
+
+    /// Stops tracing the inferior and lets it carry on running. Any breakpoints must already be
+    /// removed.
+    pub fn detach(mut self) -> Result<(), nix::Error> {
+        for index in 0..self.threads.len() {
+            let tid = self.threads[index].tid;
+            // A SIGSTOP still on its way would stop the whole process once we let go
+            while self.threads[index].stopping {
+                ptrace::cont(tid, None)?;
+                match waitpid(tid, Some(WAIT_FLAGS))? {
+                    WaitStatus::Stopped(_, signal::Signal::SIGSTOP) => {
+                        self.threads[index].stopping = false
+                    }
+                    WaitStatus::Stopped(_, signal) => self.threads[index].signal = Some(signal),
+                    _ => break,
+                }
+            }
+            ptrace::detach(tid, self.threads[index].signal)?;
+        }
+        Ok(())
+    }
+
+    /// Returns the pid of this inferior.
+    pub fn pid(&self) -> Pid {
+        self.pid
+    }
+
+    pub fn is_attached(&self) -> bool {
+        self.attached
+    }
+
+    /// The tid of the selected thread.
+    pub fn tid(&self) -> Pid {
+        self.selected
+    }
+
+    /// The id of the selected thread.
+    pub fn selected_thread(&self) -> usize {
+        self.thread(self.selected).map_or(0, |thread| thread.id)
+    }
+
+    /// Makes thread `id` the selected thread. Returns false if there is no such thread.
+    pub fn select_thread(&mut self, id: usize) -> bool {
+        match self.threads.iter().find(|thread| thread.id == id) {
+            Some(thread) => {
+                self.selected = thread.tid;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// The inferior's threads, oldest first, with where each one is stopped.
+    pub fn threads(&self, debug_data: &DwarfData) -> Result<Vec<Thread>, nix::Error> {
+        self.threads
+            .iter()
+            .map(|thread| {
+                let rip = ptrace::getregs(thread.tid)?.rip as usize;
+                Ok(Thread {
+                    id: thread.id,
+                    tid: thread.tid.as_raw(),
+                    selected: thread.tid == self.selected,
+                    frame: Frame {
+                        rip,
+                        function: debug_data.get_function_from_addr(rip),
+                        line: debug_data.get_line_from_addr(rip),
+                    },
+                })
+            })
+            .collect()
+    }
+
+    /// The ids of the threads whose stop deet has reported: all but the ones moved back onto a
+    /// trap to take it again.
+    pub fn reported_threads(&self) -> Vec<usize> {
+        self.threads
+            .iter()
+            .filter(|thread| !thread.retrap)
+            .map(|thread| thread.id)
+            .collect()
+    }
+
+    fn thread(&self, tid: Pid) -> Option<&TracedThread> {
+        self.threads.iter().find(|thread| thread.tid == tid)
+    }
+
+    fn thread_mut(&mut self, tid: Pid) -> Option<&mut TracedThread> {
+        self.threads.iter_mut().find(|thread| thread.tid == tid)
+    }
+
+    fn add_thread(&mut self, tid: Pid) {
+        self.threads.push(TracedThread {
+            id: self.next_thread_id,
+            tid,
+            signal: None,
+            stopping: false,
+            retrap: false,
+        });
+        self.next_thread_id += 1;
+    }
+
+    /// Takes on the thread `tid` that a traced thread has just created, once it has made its
+    /// first stop, and leaves it stopped. Returns false if the thread had already reported that
+    /// stop and been taken on.
+    fn add_new_thread(&mut self, tid: Pid) -> Result<bool, nix::Error> {
+        if self.thread(tid).is_some() {
+            return Ok(false);
+        }
+        waitpid(tid, Some(WAIT_FLAGS))?;
+        self.add_thread(tid);
+        Ok(true)
+    }
+
+    /// Forgets the thread `tid`, which has exited. Returns its id, if there was such a thread.
+    fn remove_thread(&mut self, tid: Pid) -> Option<usize> {
+        let index = self.threads.iter().position(|thread| thread.tid == tid)?;
+        let id = self.threads.remove(index).id;
+        if self.selected == tid {
+            self.selected = self.pid;
+        }
+        Some(id)
+    }
+
+    /// Calls waitpid on one of the inferior's threads and returns a Status to indicate its state
+    /// after the waitpid call.
+    fn wait_thread(&self, tid: Pid) -> Result<Status, BoxError> {
+        Ok(match waitpid(tid, Some(WAIT_FLAGS))? {
+            WaitStatus::Exited(_pid, exit_code) => Status::Exited(exit_code),
+            WaitStatus::Signaled(_pid, signal, _core_dumped) => Status::Signaled(signal),
+            WaitStatus::Stopped(_pid, signal) => {
+                let regs = ptrace::getregs(tid)?;
+                Status::Stopped(signal, regs.rip as usize)
+            }
+            other => return Err(unexpected_status(other)),
+        })
+    }
+
+    /// Waits until a resumed thread stops, which selects it, or the process ends. New threads,
+    /// threads exiting and the SIGSTOPs deet sent are dealt with on the way. `stepping` is the
+    /// thread being single-stepped, if only one thread is running.
+    fn wait_for_stop(&mut self, stepping: Option<Pid>) -> Result<Status, BoxError> {
+        // Lets a thread that stopped for deet's own reasons carry on the way it was going
+        let carry_on = |tid: Pid| {
+            if stepping == Some(tid) {
+                ptrace::step(tid, None)
+            } else {
+                ptrace::cont(tid, None)
+            }
+        };
+        loop {
+            match waitpid(None, Some(WAIT_FLAGS))? {
+                WaitStatus::PtraceEvent(tid, _, libc::PTRACE_EVENT_CLONE) => {
+                    let new_tid = Pid::from_raw(ptrace::getevent(tid)? as i32);
+                    if self.add_new_thread(new_tid)? && stepping.is_none() {
+                        ptrace::cont(new_tid, None)?;
+                    }
+                    carry_on(tid)?;
+                }
+                WaitStatus::Stopped(tid, signal::Signal::SIGSTOP)
+                    if self.thread(tid).is_none_or(|thread| thread.stopping) =>
+                {
+                    match self.thread_mut(tid) {
+                        Some(thread) => {
+                            thread.stopping = false;
+                            carry_on(tid)?;
+                        }
+                        // A new thread's first stop, which came before its creator's clone event
+                        None => {
+                            self.add_thread(tid);
+                            if stepping.is_none() {
+                                ptrace::cont(tid, None)?;
+                            }
+                        }
+                    }
+                }
+                WaitStatus::Stopped(tid, signal) => {
+                    self.selected = tid;
+                    let regs = ptrace::getregs(tid)?;
+                    return Ok(Status::Stopped(signal, regs.rip as usize));
+                }
+                WaitStatus::Exited(tid, exit_code) if tid == self.pid => {
+                    return Ok(Status::Exited(exit_code))
+                }
+                WaitStatus::Signaled(tid, signal, _core_dumped) if tid == self.pid => {
+                    return Ok(Status::Signaled(signal))
+                }
+                WaitStatus::Exited(tid, _) | WaitStatus::Signaled(tid, ..) => {
+                    let id = self.remove_thread(tid);
+                    if let (Some(id), true) = (id, stepping == Some(tid)) {
+                        return Ok(Status::ThreadExited(id));
+                    }
+                }
+                other => return Err(unexpected_status(other)),
+            }
+        }
+    }
+
+    /// Stops every thread but the selected one, which has just stopped. A thread that traps at a
+    /// breakpoint first is moved back to take the trap again when it is resumed; one that gets
+    /// some other signal keeps it to pass on then.
+    fn stop_others(&mut self) -> Result<(), BoxError> {
+        let mut waiting = Vec::new();
+        for thread in self.threads.iter_mut() {
+            if thread.tid == self.selected {
+                continue;
+            }
+            let result = unsafe {
+                libc::syscall(
+                    libc::SYS_tgkill,
+                    self.pid.as_raw(),
+                    thread.tid.as_raw(),
+                    libc::SIGSTOP,
+                )
+            };
+            nix::errno::Errno::result(result)?;
+            thread.stopping = true;
+            waiting.push(thread.tid);
+        }
+        while !waiting.is_empty() {
+            let tid = match waitpid(None, Some(WAIT_FLAGS))? {
+                WaitStatus::Stopped(tid, signal::Signal::SIGSTOP) => {
+                    match self.thread_mut(tid) {
+                        Some(thread) => thread.stopping = false,
+                        None => self.add_thread(tid),
+                    }
+                    tid
+                }
+                WaitStatus::Stopped(tid, signal) => {
+                    let mut regs = ptrace::getregs(tid)?;
+                    let rip = regs.rip as usize;
+                    let trapped = signal == signal::Signal::SIGTRAP
+                        && self.read_bytes(rip - 1, 1)? == [TRAP_BYTE];
+                    if trapped {
+                        regs.rip -= 1;
+                        ptrace::setregs(tid, regs)?;
+                    }
+                    if let Some(thread) = self.thread_mut(tid) {
+                        if trapped {
+                            thread.retrap = true;
+                        } else {
+                            thread.signal = Some(signal);
+                        }
+                    }
+                    tid
+                }
+                WaitStatus::PtraceEvent(tid, _, libc::PTRACE_EVENT_CLONE) => {
+                    let new_tid = Pid::from_raw(ptrace::getevent(tid)? as i32);
+                    self.add_new_thread(new_tid)?;
+                    tid
+                }
+                WaitStatus::Exited(tid, _) | WaitStatus::Signaled(tid, ..) => {
+                    self.remove_thread(tid);
+                    tid
+                }
+                other => return Err(unexpected_status(other)),
+            };
+            waiting.retain(|&waiting_tid| waiting_tid != tid);
+        }
+        Ok(())
+    }
+
+    /// Resumes every thread and waits until one of them stops, which selects it, or the process
+    /// ends. The other threads are then stopped too.
+    pub fn cont(&mut self) -> Result<Status, BoxError> {
+        for thread in self.threads.iter_mut() {
+            ptrace::cont(thread.tid, thread.signal.take())?;
+            thread.retrap = false;
+        }
+        let status = self.wait_for_stop(None)?;
+        if let Status::Stopped(..) = status {
+            self.stop_others()?;
+        }
+        Ok(status)
+    }
+
+    pub fn terminate(&mut self) -> Result<Status, nix::Error> {
+        signal::kill(self.pid(), signal::Signal::SIGKILL)?;
+        // The other threads die too, and are reported before the main thread
+        loop {
+            match waitpid(None, Some(WAIT_FLAGS))? {
+                WaitStatus::Exited(tid, exit_code) if tid == self.pid => {
+                    return Ok(Status::Exited(exit_code))
+                }
+                WaitStatus::Signaled(tid, signal, _core_dumped) if tid == self.pid => {
+                    return Ok(Status::Signaled(signal))
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Walks the selected thread's frame pointer chain from the current frame up to `main` (or
+    /// the function the thread started in).
+    pub fn backtrace(&self, debug_data: &DwarfData) -> Result<Vec<Frame>, nix::Error> {
+        let regs = ptrace::getregs(self.tid())?;
+        let mut rip = regs.rip as usize;
+        let mut rbp = regs.rbp as usize;
+        let mut frames = Vec::new();
+        loop {
+            let frame = Frame {
+                rip,
+                function: debug_data.get_function_from_addr(rip),
+                line: debug_data.get_line_from_addr(rip),
+            };
+            // Without debugging information for a frame we can't tell where its caller is
+            let done = frame.line.is_none()
+                || frame.function.is_none()
+                || frame.function.as_deref() == Some("main");
+            frames.push(frame);
+            if done {
+                break;
+            }
+            rip = ptrace::read(self.tid(), (rbp + 8) as ptrace::AddressType)? as usize;
+            rbp = ptrace::read(self.tid(), rbp as ptrace::AddressType)? as usize;
+        }
+        Ok(frames)
+    }
+
+    pub fn write_byte(&mut self, addr: usize, val: u8) -> Result<u8, nix::Error> {
+        let aligned_addr = align_addr_to_word(addr);
+        let byte_offset = addr - aligned_addr;
+        let word = ptrace::read(self.tid(), aligned_addr as ptrace::AddressType)? as u64;
+        let origin_byte = (word >> (8 * byte_offset)) & 0xff;
+        let masked_word = word & !(0xff << (8 * byte_offset));
+        let updated_word = masked_word | ((val as u64) << (8 * byte_offset));
+        ptrace::write(
+            self.tid(),
+            aligned_addr as ptrace::AddressType,
+            updated_word as *mut std::ffi::c_void,
+        )?;
+        Ok(origin_byte as u8)
+    }
+
+    /// Writes the trap byte at the breakpoint's address, remembering the byte it replaced.
+    pub fn install(&mut self, breakpoint: &mut Breakpoint) -> Result<(), nix::Error> {
+        if !breakpoint.is_installed() {
+            breakpoint.orig_byte = Some(self.write_byte(breakpoint.addr, TRAP_BYTE)?);
+        }
+        Ok(())
+    }
+
+    /// Puts back the instruction byte the breakpoint's trap replaced.
+    pub fn uninstall(&mut self, breakpoint: &mut Breakpoint) -> Result<(), nix::Error> {
+        if let Some(orig_byte) = breakpoint.orig_byte {
+            self.write_byte(breakpoint.addr, orig_byte)?;
+            breakpoint.orig_byte = None;
+        }
+        Ok(())
+    }
+
+    /// Executes the single instruction at `rip`, with `orig_byte` standing in for a breakpoint's
+    /// trap there for the duration, and returns the status after the step.
+    pub fn step_over(&mut self, rip: usize, orig_byte: u8) -> Result<Status, BoxError> {
+        self.write_byte(rip, orig_byte)?;
+        let status = self.step_instruction()?;
+        if let Status::Stopped(..) = status {
+            self.write_byte(rip, TRAP_BYTE)?;
+        }
+        Ok(status)
+    }
+
+    /// Moves the selected thread's instruction pointer to `rip`.
+    pub fn set_rip(&self, rip: usize) -> Result<(), nix::Error> {
+        let mut regs = ptrace::getregs(self.tid())?;
+        regs.rip = rip as u64;
+        ptrace::setregs(self.tid(), regs)
+    }
+
+    pub fn rip(&self) -> Result<usize, nix::Error> {
+        Ok(ptrace::getregs(self.tid())?.rip as usize)
+    }
+
+    pub fn regs(&self) -> Result<libc::user_regs_struct, nix::Error> {
+        ptrace::getregs(self.tid())
+    }
+
+    /// Reads the word of the inferior's memory at `addr`.
+    pub fn read_word(&self, addr: usize) -> Result<usize, nix::Error> {
+        Ok(ptrace::read(self.tid(), addr as ptrace::AddressType)? as usize)
+    }
+
+    /// Reads `len` bytes of the inferior's memory starting at `addr`.
+    pub fn read_bytes(&self, addr: usize, len: usize) -> Result<Vec<u8>, nix::Error> {
+        if len == 0 {
+            return Ok(Vec::new());
+        }
+        let mut bytes = Vec::with_capacity(len + size_of::<usize>());
+        let mut word_addr = align_addr_to_word(addr);
+        while word_addr < addr + len {
+            bytes.extend_from_slice(&self.read_word(word_addr)?.to_ne_bytes());
+            word_addr += size_of::<usize>();
+        }
+        let start = addr - align_addr_to_word(addr);
+        Ok(bytes[start..start + len].to_vec())
+    }
+
+    /// Reads the NUL-terminated string at `addr`, without the NUL. Stops after `max_len` bytes if
+    /// there is no NUL by then.
+    pub fn read_c_string(&self, addr: usize, max_len: usize) -> Result<Vec<u8>, nix::Error> {
+        let mut string = Vec::new();
+        let mut word_addr = align_addr_to_word(addr);
+        let mut skip = addr - word_addr;
+        while string.len() < max_len {
+            let word = self.read_word(word_addr)?.to_ne_bytes();
+            for &byte in &word[skip..] {
+                if byte == 0 || string.len() == max_len {
+                    return Ok(string);
+                }
+                string.push(byte);
+            }
+            word_addr += size_of::<usize>();
+            skip = 0;
+        }
+        Ok(string)
+    }
+
+    /// Executes a single instruction in the selected thread, with the other threads kept
+    /// stopped.
+    pub fn step_instruction(&mut self) -> Result<Status, BoxError> {
+        let tid = self.tid();
+        let mut signal = None;
+        if let Some(thread) = self.thread_mut(tid) {
+            signal = thread.signal.take();
+            thread.retrap = false;
+        }
+        ptrace::step(tid, signal)?;
+        self.wait_for_stop(Some(tid))
+    }
+}
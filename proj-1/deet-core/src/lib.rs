@@ -0,0 +1,21 @@
+//! The debugging engine behind deet: it loads a target's debugging information, runs the target
+//! under ptrace and controls it. Frontends (deet's REPL is one) drive it through a `Session`.
+
+mod breakpoint;
+mod condition;
+pub mod dwarf_data;
+mod gimli_wrapper;
+mod inferior;
+mod location;
+mod session;
+mod source;
+mod value;
+
+pub use crate::breakpoint::Breakpoint;
+pub use crate::condition::{Condition, Scope};
+pub use crate::dwarf_data::{DwarfData, Line};
+pub use crate::inferior::{Frame, Thread};
+pub use crate::location::LocationSpec;
+pub use crate::session::{Event, Session};
+pub use crate::value::Value;
+pub use nix::sys::signal::Signal;
@@ -0,0 +1,159 @@
+use errors::Error;
+use std::fmt;
+use std::str::FromStr;
+
+/// A place in the target as the user writes it, e.g. as the argument to `break`. It says nothing
+/// about whether the place exists; `Session::resolve` turns it into an address.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LocationSpec {
+    /// A raw address, written `*0x401136` (or just `0x401136`).
+    Address(usize),
+
+    /// A source line, written `file.c:23`, or `23` for a line in the first source file.
+    Line { file: Option<String>, line: usize },
+
+    /// The start of a function, written as its name.
+    Function(String),
+}
+
+impl FromStr for LocationSpec {
+    type Err = Error;
+
+    fn from_str(s: &str) -> errors::Result<LocationSpec> {
+        let invalid = || Error::Parse(format!("invalid location {}", s));
+        if let Some(addr) = s.strip_prefix('*') {
+            parse_hex(addr)
+                .map(LocationSpec::Address)
+                .ok_or_else(invalid)
+        } else if let Some((file, line)) = s.rsplit_once(':') {
+            if file.is_empty() {
+                return Err(invalid());
+            }
+            Ok(LocationSpec::Line {
+                file: Some(file.to_string()),
+                line: parse_line(line).ok_or_else(invalid)?,
+            })
+        } else if s.starts_with("0x") || s.starts_with("0X") {
+            parse_hex(s).map(LocationSpec::Address).ok_or_else(invalid)
+        } else if s.starts_with(|c: char| c.is_ascii_digit()) {
+            Ok(LocationSpec::Line {
+                file: None,
+                line: parse_line(s).ok_or_else(invalid)?,
+            })
+        } else if is_identifier(s) {
+            Ok(LocationSpec::Function(s.to_string()))
+        } else {
+            Err(invalid())
+        }
+    }
+}
+
+impl fmt::Display for LocationSpec {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LocationSpec::Address(addr) => write!(f, "*{:#x}", addr),
+            LocationSpec::Line {
+                file: Some(file),
+                line,
+            } => write!(f, "{}:{}", file, line),
+            LocationSpec::Line { file: None, line } => write!(f, "{}", line),
+            LocationSpec::Function(name) => write!(f, "{}", name),
+        }
+    }
+}
+
+/// Parses `0x`-prefixed hex. Unlike `from_str_radix`, doesn't let a sign through.
+fn parse_hex(s: &str) -> Option<usize> {
+    let hex = s.strip_prefix("0x").or_else(|| s.strip_prefix("0X"))?;
+    if hex.is_empty() || !hex.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return None;
+    }
+    usize::from_str_radix(hex, 16).ok()
+}
+
+fn parse_line(s: &str) -> Option<usize> {
+    if s.is_empty() || !s.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    s.parse().ok()
+}
+
+fn is_identifier(s: &str) -> bool {
+    let mut bytes = s.bytes();
+    match bytes.next() {
+        Some(first) if first.is_ascii_alphabetic() || first == b'_' => {
+            bytes.all(|b| b.is_ascii_alphanumeric() || b == b'_')
+        }
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn parse(s: &str) -> LocationSpec {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(parse("*0x401136"), LocationSpec::Address(0x401136));
+        assert_eq!(parse("0XfF"), LocationSpec::Address(0xff));
+        assert_eq!(
+            parse("23"),
+            LocationSpec::Line {
+                file: None,
+                line: 23
+            }
+        );
+        assert_eq!(
+            parse("samples/segfault.c:5"),
+            LocationSpec::Line {
+                file: Some("samples/segfault.c".to_string()),
+                line: 5
+            }
+        );
+        assert_eq!(
+            parse("func_3"),
+            LocationSpec::Function("func_3".to_string())
+        );
+    }
+
+    #[test]
+    fn test_parse_invalid() {
+        for s in &[
+            "",
+            "*",
+            "*main",
+            "*401136",
+            "0x",
+            "0x+1",
+            "0xfffffffffffffffff",
+            "12a",
+            "-1",
+            ":5",
+            "file.c:",
+            "file.c:+5",
+            "file.c:main",
+            "f(x)",
+            "ünïcode",
+        ] {
+            assert!(
+                matches!(s.parse::<LocationSpec>(), Err(Error::Parse(_))),
+                "{:?} should not parse",
+                s
+            );
+        }
+    }
+
+    #[test]
+    fn test_display_round_trips() {
+        for s in &["*0x401136", "23", "function_calls.c:23", "a:b.c:1", "main"] {
+            let spec = parse(s);
+            assert_eq!(spec.to_string(), *s);
+            assert_eq!(parse(&spec.to_string()), spec);
+        }
+        assert_eq!(parse("0x401136").to_string(), "*0x401136");
+    }
+}
@@ -0,0 +1,918 @@
+use crate::breakpoint::Breakpoint;
+use crate::condition::{Condition, Scope};
+use crate::dwarf_data::{
+    DwarfData, Error as DwarfError, FrameBase, Function, Location, TypeKind, Variable,
+};
+use crate::inferior::{Frame, Inferior, Status, Thread, TRAP_BYTE};
+use crate::location::LocationSpec;
+use crate::source::SourceCache;
+use crate::value::{Value, MAX_STRING_LEN};
+use errors::Error;
+use nix::sys::signal::Signal;
+use nix::unistd::Pid;
+use std::collections::VecDeque;
+use std::fs;
+
+/// Something that happened to the inferior, reported through `Session::events()`.
+#[derive(Debug)]
+pub enum Event {
+    /// The inferior hit breakpoint `id`, which is at `addr`. The inferior is stopped with its
+    /// instruction pointer at `addr`.
+    BreakpointHit { id: usize, addr: usize },
+
+    /// The inferior stopped because of `signal` (other than at a breakpoint). `rip` is the
+    /// instruction pointer it stopped at.
+    Stopped { signal: Signal, rip: usize },
+
+    /// The inferior exited normally. Contains the exit status code.
+    Exited(i32),
+
+    /// The inferior was killed by a signal.
+    Signaled(Signal),
+
+    /// A `step`, `next` or `finish` completed. The inferior is stopped at `rip`.
+    Stepped { rip: usize },
+
+    /// Thread `id` exited while it was being stepped. The main thread is selected in its place.
+    ThreadExited(usize),
+
+    /// Something went wrong that didn't stop the operation in progress, e.g. a breakpoint that
+    /// couldn't be installed when the target was launched.
+    Warning(Error),
+}
+
+/// A debugging session for one target program. It owns the target's debugging information,
+/// the breakpoints set so far, and the running inferior (if there is one).
+///
+/// Operations that run the inferior don't return what happened to it; instead they queue
+/// `Event`s, which the frontend drains with `events()`.
+pub struct Session {
+    target: String,
+    debug_data: DwarfData,
+    inferior: Option<Inferior>,
+    breakpoints: Vec<Breakpoint>,
+    next_breakpoint_id: usize,
+    events: VecDeque<Event>,
+    sources: SourceCache,
+}
+
+impl Session {
+    /// Loads the debugging information for `target`. The target isn't started until
+    /// `launch()`.
+    pub fn new(target: &str) -> errors::Result<Session> {
+        Ok(Session {
+            target: target.to_string(),
+            debug_data: load_debug_data(target)?,
+            inferior: None,
+            breakpoints: Vec::new(),
+            next_breakpoint_id: 0,
+            events: VecDeque::new(),
+            sources: SourceCache::new(),
+        })
+    }
+
+    pub fn target(&self) -> &str {
+        &self.target
+    }
+
+    pub fn debug_data(&self) -> &DwarfData {
+        &self.debug_data
+    }
+
+    /// Finds the address `location` refers to in the target.
+    pub fn resolve(&self, location: &LocationSpec) -> errors::Result<usize> {
+        let addr = match location {
+            LocationSpec::Address(addr) => Some(*addr),
+            LocationSpec::Line { file, line } => {
+                self.debug_data.get_addr_for_line(file.as_deref(), *line)
+            }
+            LocationSpec::Function(name) => self.debug_data.get_addr_for_function(None, name),
+        };
+        addr.ok_or_else(|| Error::Parse(format!("no location {} in {}", location, self.target)))
+    }
+
+    /// The source file and line `location` refers to, or by default the line the selected thread
+    /// is stopped at (or `main`'s, before the target runs). A line location is taken as it is,
+    /// whether or not the line has any code.
+    pub fn source_line(&self, location: Option<&LocationSpec>) -> errors::Result<(String, usize)> {
+        let addr = match location {
+            Some(LocationSpec::Line { file, line }) => {
+                let path = self
+                    .debug_data
+                    .get_source_file(file.as_deref())
+                    .ok_or_else(|| {
+                        Error::Parse(match file {
+                            Some(file) => format!("no source file {} in {}", file, self.target),
+                            None => format!("no source files in {}", self.target),
+                        })
+                    })?;
+                return Ok((path.to_string(), *line));
+            }
+            Some(location) => self.resolve(location)?,
+            None if self.is_running() => self.rip()?,
+            None => self.resolve(&LocationSpec::Function("main".to_string()))?,
+        };
+        self.debug_data
+            .get_line_from_addr(addr)
+            .map(|line| (line.file, line.number))
+            .ok_or_else(|| Error::Parse(format!("no source line for {:#x}", addr)))
+    }
+
+    /// Up to `count` lines of the source file at `path`, starting at line `first`, each with its
+    /// number (counting from 1). The file is read the first time it is listed and kept after that.
+    pub fn source_lines(
+        &mut self,
+        path: &str,
+        first: usize,
+        count: usize,
+    ) -> errors::Result<Vec<(usize, String)>> {
+        Ok(self
+            .sources
+            .lines(path)?
+            .iter()
+            .enumerate()
+            .skip(first.saturating_sub(1))
+            .take(count)
+            .map(|(index, text)| (index + 1, text.clone()))
+            .collect())
+    }
+
+    /// The breakpoints set so far (and not deleted), in the order they were set.
+    pub fn breakpoints(&self) -> &[Breakpoint] {
+        &self.breakpoints
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.inferior.is_some()
+    }
+
+    /// Whether the inferior is a process we attached to, rather than one we started.
+    pub fn is_attached(&self) -> bool {
+        matches!(&self.inferior, Some(inferior) if inferior.is_attached())
+    }
+
+    /// The inferior's process id, if there is one.
+    pub fn pid(&self) -> Option<i32> {
+        self.inferior
+            .as_ref()
+            .map(|inferior| inferior.pid().as_raw())
+    }
+
+    /// The inferior's threads, oldest first, with where each one is stopped.
+    pub fn threads(&self) -> errors::Result<Vec<Thread>> {
+        self.inferior()?
+            .threads(&self.debug_data)
+            .map_err(|err| Error::ptrace("can not read threads", err))
+    }
+
+    /// The id of the selected thread, which registers and variables are read from and which
+    /// steps. Whenever the inferior stops, the thread that stopped it is selected.
+    pub fn selected_thread(&self) -> Option<usize> {
+        self.inferior
+            .as_ref()
+            .map(|inferior| inferior.selected_thread())
+    }
+
+    /// Selects thread `id` (gdb's `thread`).
+    pub fn select_thread(&mut self, id: usize) -> errors::Result<()> {
+        if self.inferior_mut()?.select_thread(id) {
+            Ok(())
+        } else {
+            Err(Error::Parse(format!("no thread {}", id)))
+        }
+    }
+
+    /// Starts a session for the running process `pid`, with the debugging information of its
+    /// executable, and attaches to it.
+    pub fn attach_to(pid: i32) -> errors::Result<Session> {
+        let mut session = Session::new(&format!("/proc/{}/exe", pid))?;
+        session.attach(pid)?;
+        Ok(session)
+    }
+
+    /// Attaches to the running process `pid`, which stays stopped. The process's executable
+    /// becomes the target: its debugging information is loaded (from `/proc/<pid>/exe`), and the
+    /// breakpoints are resolved again against it and installed. A breakpoint that no longer
+    /// resolves is disabled, with a warning. A previous inferior is let go (see `release`) once
+    /// attaching has worked; if that fails, the new process is detached from again.
+    pub fn attach(&mut self, pid: i32) -> errors::Result<()> {
+        let exe = format!("/proc/{}/exe", pid);
+        let target = fs::read_link(&exe).map_err(|err| {
+            Error::io(
+                format!("can not find the executable of process {}", pid),
+                err,
+            )
+        })?;
+        let debug_data = load_debug_data(&exe)?;
+        let inferior = Inferior::attach(Pid::from_raw(pid))?;
+        if self.inferior.is_some() {
+            if let Err(err) = self.release() {
+                // Nobody would be left to let the new process carry on
+                let _ = inferior.detach();
+                return Err(err);
+            }
+        }
+        self.target = target.to_string_lossy().into_owned();
+        self.debug_data = debug_data;
+        self.inferior = Some(inferior);
+
+        for index in 0..self.breakpoints.len() {
+            let bp = &mut self.breakpoints[index];
+            bp.hit_count = 0;
+            let location = bp.location.clone();
+            match self.resolve(&location) {
+                Ok(addr) => self.breakpoints[index].addr = addr,
+                Err(err) => {
+                    self.breakpoints[index].enabled = false;
+                    self.events.push_back(Event::Warning(err));
+                    continue;
+                }
+            }
+            if !self.breakpoints[index].enabled {
+                continue;
+            }
+            if let Err(err) = self.install(index) {
+                self.events.push_back(Event::Warning(err));
+            }
+        }
+        let rip = self.rip()?;
+        self.events.push_back(Event::Stopped {
+            signal: Signal::SIGSTOP,
+            rip,
+        });
+        Ok(())
+    }
+
+    /// Removes every breakpoint's trap from the inferior and lets it carry on running, no longer
+    /// traced.
+    pub fn detach(&mut self) -> errors::Result<()> {
+        self.inferior()?;
+        for index in 0..self.breakpoints.len() {
+            self.uninstall(index)?;
+        }
+        self.inferior
+            .take()
+            .unwrap()
+            .detach()
+            .map_err(|err| Error::ptrace("failed to detach from target", err))
+    }
+
+    /// Starts the target with `args`, installs the enabled breakpoints and runs it until it stops
+    /// or exits. A previous inferior is killed first.
+    pub fn launch(&mut self, args: &[String]) -> errors::Result<()> {
+        // make sure no previous target exists
+        if self.inferior.is_some() {
+            self.release()?;
+        }
+
+        self.inferior = Some(Inferior::new(&self.target, args)?);
+        for index in 0..self.breakpoints.len() {
+            self.breakpoints[index].hit_count = 0;
+            if !self.breakpoints[index].enabled {
+                continue;
+            }
+            if let Err(err) = self.install(index) {
+                self.events.push_back(Event::Warning(err));
+            }
+        }
+        let status = self
+            .inferior
+            .as_mut()
+            .unwrap()
+            .cont()
+            .map_err(|err| Error::ptrace("failed to run target", err))?;
+        if !self.record(status)? {
+            self.resume()?;
+        }
+        Ok(())
+    }
+
+    /// Sets a breakpoint at `location`, installing it right away if the target is running, and
+    /// returns its id.
+    pub fn set_breakpoint(&mut self, location: LocationSpec) -> errors::Result<usize> {
+        let addr = self.resolve(&location)?;
+        let id = self.next_breakpoint_id;
+        self.breakpoints.push(Breakpoint::new(id, location, addr));
+        if let Err(err) = self.install(self.breakpoints.len() - 1) {
+            self.breakpoints.pop();
+            return Err(err);
+        }
+        self.next_breakpoint_id += 1;
+        Ok(id)
+    }
+
+    /// Makes breakpoint `id` stop the inferior only when `condition` holds, or every time if it is
+    /// `None`.
+    pub fn set_condition(&mut self, id: usize, condition: Option<Condition>) -> errors::Result<()> {
+        let index = self.breakpoint_index(id)?;
+        self.breakpoints[index].condition = condition;
+        Ok(())
+    }
+
+    /// Makes breakpoint `id` let the inferior carry on the next `count` times it reaches it with
+    /// its condition holding (gdb's `ignore`).
+    pub fn set_ignore_count(&mut self, id: usize, count: usize) -> errors::Result<()> {
+        let index = self.breakpoint_index(id)?;
+        self.breakpoints[index].ignore_count = count;
+        Ok(())
+    }
+
+    /// Deletes breakpoint `id`, restoring the original instruction if it is installed.
+    pub fn delete_breakpoint(&mut self, id: usize) -> errors::Result<()> {
+        let index = self.breakpoint_index(id)?;
+        self.uninstall(index)?;
+        self.breakpoints.remove(index);
+        Ok(())
+    }
+
+    /// Enables breakpoint `id`, installing it in the running inferior. Its location is resolved
+    /// again first, since it may have been disabled for not resolving in a process attached to
+    /// since, in which case its address is stale.
+    pub fn enable_breakpoint(&mut self, id: usize) -> errors::Result<()> {
+        let index = self.breakpoint_index(id)?;
+        if !self.breakpoints[index].is_installed() {
+            let location = self.breakpoints[index].location.clone();
+            self.breakpoints[index].addr = self.resolve(&location)?;
+        }
+        self.install(index)?;
+        self.breakpoints[index].enabled = true;
+        Ok(())
+    }
+
+    /// Disables breakpoint `id`, restoring the original instruction in the running inferior.
+    pub fn disable_breakpoint(&mut self, id: usize) -> errors::Result<()> {
+        let index = self.breakpoint_index(id)?;
+        self.uninstall(index)?;
+        self.breakpoints[index].enabled = false;
+        Ok(())
+    }
+
+    /// Resumes a stopped inferior until it stops again or exits, first stepping it over the
+    /// breakpoint it is stopped at, if any.
+    pub fn resume(&mut self) -> errors::Result<()> {
+        loop {
+            let status = self.continue_inferior()?;
+            if self.record(status)? {
+                return Ok(());
+            }
+        }
+    }
+
+    /// Runs the inferior until it reaches the start of another source line, following calls into
+    /// functions that have debugging information (gdb's `step`).
+    pub fn step(&mut self) -> errors::Result<()> {
+        self.step_line(false)
+    }
+
+    /// Like `step`, but runs any function called on the current line to completion (gdb's
+    /// `next`).
+    pub fn step_over_calls(&mut self) -> errors::Result<()> {
+        self.step_line(true)
+    }
+
+    /// Runs the inferior until the current function returns to its caller (gdb's `finish`).
+    pub fn finish(&mut self) -> errors::Result<()> {
+        let slot = self.return_address_slot()?;
+        let ret_addr = self
+            .inferior()?
+            .read_word(slot)
+            .map_err(|err| Error::ptrace("can not read return address", err))?;
+        if self.run_to_return(ret_addr, slot + 8)? {
+            self.events.push_back(Event::Stepped { rip: ret_addr });
+        }
+        Ok(())
+    }
+
+    /// Gets rid of the inferior: detaches from a process we attached to, which the user
+    /// presumably wants to keep running, and kills one we started.
+    pub fn release(&mut self) -> errors::Result<()> {
+        if self.is_attached() {
+            self.detach()
+        } else {
+            self.kill()
+        }
+    }
+
+    /// Kills the inferior.
+    pub fn kill(&mut self) -> errors::Result<()> {
+        let inferior = self.inferior.as_mut().ok_or(Error::NotRunning)?;
+        let status = inferior
+            .terminate()
+            .map_err(|err| Error::ptrace("failed to terminate target", err))?;
+        self.record(status)?;
+        Ok(())
+    }
+
+    /// The stopped inferior's call stack, innermost frame first.
+    pub fn backtrace(&self) -> errors::Result<Vec<Frame>> {
+        self.inferior
+            .as_ref()
+            .ok_or(Error::NotRunning)?
+            .backtrace(&self.debug_data)
+            .map_err(|err| Error::ptrace("failed to read backtrace", err))
+    }
+
+    /// Drains the events that happened since the last call, oldest first.
+    pub fn events(&mut self) -> impl Iterator<Item = Event> + '_ {
+        self.events.drain(..)
+    }
+
+    /// Reads the variable `name` as seen from where the inferior is stopped: a parameter or local
+    /// variable of the current function, or else a global.
+    pub fn read_variable(&self, name: &str) -> errors::Result<Value> {
+        let rip = self.rip()?;
+        if let Some(func) = self.debug_data.get_function_containing(rip) {
+            if let Some(var) = func.variables.iter().find(|var| var.name == name) {
+                return self.read_value(var, Some(func));
+            }
+        }
+        match self.debug_data.get_global_variable(name) {
+            Some(var) => self.read_value(var, None),
+            None => Err(Error::Parse(format!(
+                "no variable {} in the current context",
+                name
+            ))),
+        }
+    }
+
+    /// The parameters and local variables of the function the inferior is stopped in, with their
+    /// values. Each value is read separately, so one that can't be read doesn't hide the rest.
+    pub fn locals(&self) -> errors::Result<Vec<(String, errors::Result<Value>)>> {
+        let rip = self.rip()?;
+        Ok(match self.debug_data.get_function_containing(rip) {
+            Some(func) => func
+                .variables
+                .iter()
+                .map(|var| (var.name.clone(), self.read_value(var, Some(func))))
+                .collect(),
+            None => Vec::new(),
+        })
+    }
+
+    fn inferior(&self) -> errors::Result<&Inferior> {
+        self.inferior.as_ref().ok_or(Error::NotRunning)
+    }
+
+    fn inferior_mut(&mut self) -> errors::Result<&mut Inferior> {
+        self.inferior.as_mut().ok_or(Error::NotRunning)
+    }
+
+    fn regs(&self) -> errors::Result<libc::user_regs_struct> {
+        self.inferior()?
+            .regs()
+            .map_err(|err| Error::ptrace("can not read registers", err))
+    }
+
+    fn rip(&self) -> errors::Result<usize> {
+        self.inferior()?
+            .rip()
+            .map_err(|err| Error::ptrace("can not read %rip", err))
+    }
+
+    /// Reads inferior memory as it would be without any breakpoint traps.
+    fn read_code(&self, addr: usize, len: usize) -> errors::Result<Vec<u8>> {
+        let mut bytes = self
+            .inferior()?
+            .read_bytes(addr, len)
+            .map_err(|err| Error::ptrace(format!("can not read memory at {:#x}", addr), err))?;
+        for bp in self.breakpoints.iter() {
+            if let (Some(orig_byte), true) = (bp.orig_byte, (addr..addr + len).contains(&bp.addr)) {
+                bytes[bp.addr - addr] = orig_byte;
+            }
+        }
+        Ok(bytes)
+    }
+
+    /// Executes the single instruction at the inferior's instruction pointer, even if a
+    /// breakpoint's trap has replaced it.
+    fn step_instruction(&mut self) -> errors::Result<Status> {
+        let rip = self.rip()?;
+        let orig_byte = installed_at(&self.breakpoints, rip).and_then(|bp| bp.orig_byte);
+        let inferior = self.inferior_mut()?;
+        match orig_byte {
+            Some(orig_byte) => inferior.step_over(rip, orig_byte),
+            None => inferior.step_instruction(),
+        }
+        .map_err(|err| Error::ptrace("can not step target", err))
+    }
+
+    /// Continues the inferior (stepping its threads off the breakpoints they reported stopping at
+    /// first) and returns the status it stops with, without recording it.
+    fn continue_inferior(&mut self) -> errors::Result<Status> {
+        let selected = self.inferior()?.selected_thread();
+        for thread in self.inferior()?.reported_threads() {
+            self.select_thread(thread)?;
+            if installed_at(&self.breakpoints, self.rip()?).is_some() {
+                let status = self.step_instruction()?;
+                if !is_trap(&status) {
+                    // The one instruction was enough to crash or end the inferior
+                    return Ok(status);
+                }
+            }
+        }
+        self.select_thread(selected)?;
+        self.inferior_mut()?
+            .cont()
+            .map_err(|err| Error::ptrace("failed to continue target", err))
+    }
+
+    /// Single-steps until the inferior reaches the start of a line other than the current one.
+    /// Calls are run to completion if `over_calls` is set or the callee has no line information.
+    fn step_line(&mut self, over_calls: bool) -> errors::Result<()> {
+        let start = self.rip()?;
+        let start_line = match self.debug_data.get_line_from_addr(start) {
+            Some(line) => (line.file, line.number),
+            // With no line to step off, do what gdb does and run until the function returns
+            None => return self.finish(),
+        };
+        // A function's entry is on its opening line; the first line worth stopping at in a
+        // function we step into is the one after the prologue
+        let mut entry = None;
+        loop {
+            let before = self.regs()?;
+            let status = self.step_instruction()?;
+            if !is_trap(&status) {
+                self.record(status)?;
+                return Ok(());
+            }
+            let regs = self.regs()?;
+            let rip = regs.rip as usize;
+            if let Some(id) = self.breakpoint_stop(rip) {
+                self.events
+                    .push_back(Event::BreakpointHit { id, addr: rip });
+                return Ok(());
+            }
+
+            let mut rip = rip;
+            let mut line = self.debug_data.get_line_from_addr(rip);
+            if regs.rsp == before.rsp.wrapping_sub(8) {
+                let pushed = self
+                    .inferior()?
+                    .read_word(regs.rsp as usize)
+                    .map_err(|err| Error::ptrace("can not read stack", err))?;
+                // A call pushes the address of the instruction after it (at most 15 bytes on)
+                let before_rip = before.rip as usize;
+                if pushed > before_rip && pushed <= before_rip + 15 {
+                    if !over_calls && line.is_some() {
+                        entry = Some(rip);
+                        continue;
+                    }
+                    if !self.run_to_return(pushed, regs.rsp as usize + 8)? {
+                        return Ok(());
+                    }
+                    // The call may have been the last instruction of its line
+                    rip = pushed;
+                    line = self.debug_data.get_line_from_addr(rip);
+                }
+            }
+            match line {
+                // We returned into code without line information (e.g. from main into libc),
+                // so there is nothing left to step through
+                None => return self.resume(),
+                Some(line) => {
+                    if self.debug_data.is_line_start(rip)
+                        && entry != Some(rip)
+                        && (line.file, line.number) != start_line
+                    {
+                        self.events.push_back(Event::Stepped { rip });
+                        return Ok(());
+                    }
+                }
+            }
+        }
+    }
+
+    /// Runs the inferior until the selected thread returns to `ret_addr` with its stack pointer
+    /// at `rsp` or above, i.e. until the call that will return there has completed. Returns
+    /// false, having recorded what happened, if it stopped for some other reason first.
+    fn run_to_return(&mut self, ret_addr: usize, rsp: usize) -> errors::Result<bool> {
+        // A breakpoint already at the return address stops the inferior there anyway
+        let use_own_trap = installed_at(&self.breakpoints, ret_addr).is_none();
+        let thread = self.inferior()?.selected_thread();
+        loop {
+            let orig_byte = if use_own_trap {
+                Some(
+                    self.inferior_mut()?
+                        .write_byte(ret_addr, TRAP_BYTE)
+                        .map_err(|err| Error::ptrace("can not set return breakpoint", err))?,
+                )
+            } else {
+                None
+            };
+            let status = self.continue_inferior()?;
+            if let (Some(orig_byte), Status::Stopped(..)) = (orig_byte, &status) {
+                self.inferior_mut()?
+                    .write_byte(ret_addr, orig_byte)
+                    .map_err(|err| Error::ptrace("can not remove return breakpoint", err))?;
+            }
+            match status {
+                Status::Stopped(Signal::SIGTRAP, rip) if use_own_trap && rip - 1 == ret_addr => {
+                    let inferior = self.inferior_mut()?;
+                    inferior
+                        .set_rip(ret_addr)
+                        .map_err(|err| Error::ptrace("can not set %rip", err))?;
+                    let own_thread = inferior.selected_thread() == thread;
+                    if own_thread && self.regs()?.rsp as usize >= rsp {
+                        return Ok(true);
+                    }
+                    // A deeper, recursive call, or another thread, returned to the same place;
+                    // step off the return address so the trap can go back in, and keep going
+                    let status = self.step_instruction()?;
+                    if !is_trap(&status) {
+                        self.record(status)?;
+                        return Ok(false);
+                    }
+                    self.select_thread(thread)?;
+                }
+                _ => {
+                    if self.record(status)? {
+                        return Ok(false);
+                    }
+                    // A breakpoint that doesn't stop the inferior, which may be the one at the
+                    // return address
+                    if !use_own_trap
+                        && self.inferior()?.selected_thread() == thread
+                        && self.rip()? == ret_addr
+                        && self.regs()?.rsp as usize >= rsp
+                    {
+                        return Ok(true);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Reads `var`, a global or a variable of `func`, the function the inferior is stopped in.
+    fn read_value(&self, var: &Variable, func: Option<&Function>) -> errors::Result<Value> {
+        let ty = &var.entity_type;
+        if let TypeKind::Other = ty.kind {
+            // Don't read what can't be shown, which could be a large array
+            return Ok(Value::Unsupported(ty.name.clone()));
+        }
+        let addr = match var.location {
+            Location::Address(addr) => addr,
+            Location::FramePointerOffset(offset) => {
+                let frame_base = self.frame_base(func.and_then(|func| func.frame_base))?;
+                (frame_base as isize + offset) as usize
+            }
+        };
+        let inferior = self.inferior()?;
+        let bytes = inferior.read_bytes(addr, ty.size).map_err(|err| {
+            Error::ptrace(format!("can not read {} at {:#x}", var.name, addr), err)
+        })?;
+        Ok(match (Value::decode(ty, &bytes), &ty.kind) {
+            (Value::Pointer(addr), TypeKind::Pointer(Some(target)))
+                if addr != 0 && matches!(target.kind, TypeKind::Char { .. }) =>
+            {
+                match inferior.read_c_string(addr, MAX_STRING_LEN + 1) {
+                    Ok(mut text) => {
+                        let truncated = text.len() > MAX_STRING_LEN;
+                        text.truncate(MAX_STRING_LEN);
+                        Value::String {
+                            addr,
+                            text,
+                            truncated,
+                        }
+                    }
+                    // A dangling pointer is still worth showing
+                    Err(_) => Value::Pointer(addr),
+                }
+            }
+            (value, _) => value,
+        })
+    }
+
+    /// The address the current function's variables are located relative to.
+    fn frame_base(&self, frame_base: Option<FrameBase>) -> errors::Result<usize> {
+        match frame_base {
+            // The stack pointer before the call pushed the return address
+            Some(FrameBase::Cfa) => Ok(self.return_address_slot()? + 8),
+            // DWARF numbers %rbp 6 and %rsp 7
+            Some(FrameBase::Register(6)) => Ok(self.regs()?.rbp as usize),
+            Some(FrameBase::Register(7)) => Ok(self.regs()?.rsp as usize),
+            other => Err(Error::Dwarf {
+                target: self.target.clone(),
+                message: format!("unsupported frame base {:?}", other),
+            }),
+        }
+    }
+
+    /// Where the current function's return address is on the stack, which depends on how far
+    /// through its prologue (`push %rbp; mov %rsp,%rbp`) or epilogue the function is.
+    fn return_address_slot(&self) -> errors::Result<usize> {
+        let regs = self.regs()?;
+        let (rip, rsp, rbp) = (regs.rip as usize, regs.rsp as usize, regs.rbp as usize);
+        if self.read_code(rip, 1)? == [0xc3] {
+            // ret
+            return Ok(rsp);
+        }
+        let start = match self.debug_data.get_function_containing(rip) {
+            Some(func) => func.address,
+            None => return Ok(rbp + 8),
+        };
+        let mut push_at = start;
+        if self.read_code(start, 4)? == [0xf3, 0x0f, 0x1e, 0xfa] {
+            // endbr64
+            push_at += 4;
+        }
+        Ok(if rip <= push_at {
+            rsp
+        } else if rip == push_at + 1 {
+            rsp + 8
+        } else {
+            rbp + 8
+        })
+    }
+
+    fn breakpoint_index(&self, id: usize) -> errors::Result<usize> {
+        self.breakpoints
+            .iter()
+            .position(|bp| bp.id == id)
+            .ok_or_else(|| Error::Parse(format!("no breakpoint {}", id)))
+    }
+
+    /// Installs breakpoint `index` in the running inferior, if there is one. Breakpoints at the
+    /// same address share a single trap.
+    fn install(&mut self, index: usize) -> errors::Result<()> {
+        let inferior = match self.inferior.as_mut() {
+            Some(inferior) => inferior,
+            None => return Ok(()),
+        };
+        let addr = self.breakpoints[index].addr;
+        if let Some(shared) = installed_at(&self.breakpoints, addr) {
+            let orig_byte = shared.orig_byte;
+            self.breakpoints[index].orig_byte = orig_byte;
+            return Ok(());
+        }
+        inferior
+            .install(&mut self.breakpoints[index])
+            .map_err(|err| {
+                Error::ptrace(
+                    format!("failed to set breakpoint at position {:#x}", addr),
+                    err,
+                )
+            })
+    }
+
+    /// Removes breakpoint `index` from the running inferior, leaving the trap in place if another
+    /// breakpoint shares it.
+    fn uninstall(&mut self, index: usize) -> errors::Result<()> {
+        let addr = self.breakpoints[index].addr;
+        let shared = self
+            .breakpoints
+            .iter()
+            .enumerate()
+            .any(|(i, bp)| i != index && bp.addr == addr && bp.is_installed());
+        match self.inferior.as_mut() {
+            Some(inferior) if !shared => {
+                inferior
+                    .uninstall(&mut self.breakpoints[index])
+                    .map_err(|err| {
+                        Error::ptrace(
+                            format!("failed to remove breakpoint at position {:#x}", addr),
+                            err,
+                        )
+                    })
+            }
+            _ => {
+                self.breakpoints[index].orig_byte = None;
+                Ok(())
+            }
+        }
+    }
+
+    /// Decides whether the breakpoints at `addr`, which the inferior has just reached, stop it.
+    /// Counts a hit for each one whose condition holds, and uses up ignore counts. Returns the
+    /// breakpoint to report, if any. A condition that can't be evaluated stops the inferior, with
+    /// a warning.
+    fn breakpoint_stop(&mut self, addr: usize) -> Option<usize> {
+        let indices: Vec<usize> = (0..self.breakpoints.len())
+            .filter(|&index| {
+                let bp = &self.breakpoints[index];
+                bp.addr == addr && bp.is_installed()
+            })
+            .collect();
+        let mut stop = None;
+        for index in indices {
+            let bp = &self.breakpoints[index];
+            let id = bp.id;
+            let holds = match bp
+                .condition
+                .as_ref()
+                .map(|condition| condition.evaluate(self))
+            {
+                None => true,
+                Some(Ok(holds)) => holds,
+                Some(Err(err)) => {
+                    let warning =
+                        Error::Parse(format!("error in condition of breakpoint {}: {}", id, err));
+                    self.events.push_back(Event::Warning(warning));
+                    true
+                }
+            };
+            if !holds {
+                continue;
+            }
+            let bp = &mut self.breakpoints[index];
+            bp.hit_count += 1;
+            if bp.ignore_count > 0 {
+                bp.ignore_count -= 1;
+            } else if stop.is_none() {
+                stop = Some(bp.id);
+            }
+        }
+        stop
+    }
+
+    /// Queues the event for a status the inferior reported. If it stopped at a breakpoint, moves
+    /// it back to the start of the trapped instruction; if it is no longer running, forgets it.
+    ///
+    /// Returns false, and queues nothing, if it stopped at breakpoints that don't stop it (their
+    /// conditions are false or they are being ignored); it is then ready to be continued.
+    fn record(&mut self, status: Status) -> errors::Result<bool> {
+        let event = match status {
+            Status::Stopped(Signal::SIGTRAP, rip)
+                if installed_at(&self.breakpoints, rip - 1).is_some() =>
+            {
+                self.inferior()?
+                    .set_rip(rip - 1)
+                    .map_err(|err| Error::ptrace("can not set %rip", err))?;
+                match self.breakpoint_stop(rip - 1) {
+                    Some(id) => Event::BreakpointHit { id, addr: rip - 1 },
+                    None => return Ok(false),
+                }
+            }
+            Status::Stopped(signal, rip) => Event::Stopped { signal, rip },
+            Status::Exited(exit_code) => Event::Exited(exit_code),
+            Status::Signaled(signal) => Event::Signaled(signal),
+            Status::ThreadExited(id) => Event::ThreadExited(id),
+        };
+        if let Event::Exited(_) | Event::Signaled(_) = event {
+            self.inferior = None;
+            for bp in self.breakpoints.iter_mut() {
+                bp.orig_byte = None;
+            }
+        }
+        self.events.push_back(event);
+        Ok(true)
+    }
+}
+
+/// Conditions see the stopped frame's variables, as `print` does, and its registers.
+impl Scope for Session {
+    fn variable(&self, name: &str) -> errors::Result<i64> {
+        self.read_variable(name)?
+            .to_integer()
+            .ok_or_else(|| Error::Parse(format!("{} is not an integer", name)))
+    }
+
+    fn register(&self, name: &str) -> errors::Result<i64> {
+        let regs = self.regs()?;
+        let value = match name {
+            "rax" => regs.rax,
+            "rbx" => regs.rbx,
+            "rcx" => regs.rcx,
+            "rdx" => regs.rdx,
+            "rsi" => regs.rsi,
+            "rdi" => regs.rdi,
+            "rbp" => regs.rbp,
+            "rsp" => regs.rsp,
+            "r8" => regs.r8,
+            "r9" => regs.r9,
+            "r10" => regs.r10,
+            "r11" => regs.r11,
+            "r12" => regs.r12,
+            "r13" => regs.r13,
+            "r14" => regs.r14,
+            "r15" => regs.r15,
+            "rip" => regs.rip,
+            "eflags" => regs.eflags,
+            _ => return Err(Error::Parse(format!("no register ${}", name))),
+        };
+        Ok(value as i64)
+    }
+}
+
+fn load_debug_data(target: &str) -> errors::Result<DwarfData> {
+    DwarfData::from_file(target).map_err(|err| match err {
+        DwarfError::ErrorOpeningFile(err) => {
+            Error::io(format!("could not open file {}", target), err)
+        }
+        DwarfError::DwarfFormatError(err) => Error::Dwarf {
+            target: target.to_string(),
+            message: format!("{:?}", err),
+        },
+    })
+}
+
+/// Whether the inferior stopped the way it does after a single step or at a trap.
+fn is_trap(status: &Status) -> bool {
+    matches!(status, Status::Stopped(Signal::SIGTRAP, _))
+}
+
+/// The breakpoint whose trap is installed at `addr`, if any.
+fn installed_at(breakpoints: &[Breakpoint], addr: usize) -> Option<&Breakpoint> {
+    breakpoints
+        .iter()
+        .find(|bp| bp.addr == addr && bp.is_installed())
+}
@@ -0,0 +1,70 @@
+use errors::Error;
+use std::collections::HashMap;
+use std::fs;
+
+/// The source files listed so far, each read from disk the first time it is asked for.
+#[derive(Debug, Default)]
+pub struct SourceCache {
+    files: HashMap<String, Vec<String>>,
+}
+
+impl SourceCache {
+    pub fn new() -> SourceCache {
+        SourceCache::default()
+    }
+
+    /// The lines of the file at `path`, without their line endings. Bytes that aren't UTF-8 are
+    /// replaced rather than failing the whole file.
+    pub fn lines(&mut self, path: &str) -> errors::Result<&[String]> {
+        if !self.files.contains_key(path) {
+            let bytes = fs::read(path)
+                .map_err(|err| Error::io(format!("can not read source file {}", path), err))?;
+            let lines = String::from_utf8_lossy(&bytes)
+                .lines()
+                .map(str::to_string)
+                .collect();
+            self.files.insert(path.to_string(), lines);
+        }
+        Ok(&self.files[path])
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::env;
+    use std::process;
+
+    #[test]
+    fn test_lines() {
+        let path = format!("{}/../deet/samples/loop.c", env!("CARGO_MANIFEST_DIR"));
+        let mut cache = SourceCache::new();
+        let lines = cache.lines(&path).unwrap();
+        assert_eq!(lines.len(), 14);
+        assert_eq!(lines[2], "int square(int n) {");
+        assert_eq!(lines[13], "}");
+    }
+
+    #[test]
+    fn test_lines_are_cached() {
+        let path = env::temp_dir().join(format!("deet-source-{}.c", process::id()));
+        let path = path.to_str().unwrap();
+        fs::write(path, b"int main() {\r\n    return '\xff';\n}").unwrap();
+        let mut cache = SourceCache::new();
+        assert_eq!(
+            cache.lines(path).unwrap(),
+            ["int main() {", "    return '\u{fffd}';", "}"]
+        );
+        fs::remove_file(path).unwrap();
+        assert_eq!(cache.lines(path).unwrap().len(), 3);
+    }
+
+    #[test]
+    fn test_missing_file() {
+        let mut cache = SourceCache::new();
+        assert!(matches!(
+            cache.lines("/nonexistent/deet/source.c"),
+            Err(Error::Io { .. })
+        ));
+    }
+}
@@ -0,0 +1,188 @@
+use crate::dwarf_data::{Type, TypeKind};
+use std::convert::TryInto;
+use std::{ascii, fmt};
+
+/// The value of a variable in the inferior.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Signed(i64),
+    Unsigned(u64),
+    /// A character type, with its numeric value (negative for a `signed char` above 127).
+    Char(i64),
+    Bool(bool),
+    Float(f64),
+    Pointer(usize),
+    /// A `char *` and the string it points to. `truncated` is set if the string went on past
+    /// `MAX_STRING_LEN` bytes.
+    String {
+        addr: usize,
+        text: Vec<u8>,
+        truncated: bool,
+    },
+    /// A value of a type deet doesn't know how to show, e.g. a struct, named by its type.
+    Unsupported(String),
+}
+
+/// How much of a string `print` reads before giving up on finding its end.
+pub const MAX_STRING_LEN: usize = 200;
+
+impl Value {
+    /// Interprets the `ty.size` bytes of a value of type `ty`, in x86-64 (little-endian) byte
+    /// order. Pointers come back as `Pointer`; following a `char *` to its string is up to the
+    /// caller.
+    pub fn decode(ty: &Type, bytes: &[u8]) -> Value {
+        if bytes.len() != ty.size || ty.size == 0 || ty.size > 8 {
+            return Value::Unsupported(ty.name.clone());
+        }
+        let mut buf = [0; 8];
+        buf[..bytes.len()].copy_from_slice(bytes);
+        let unsigned = u64::from_le_bytes(buf);
+        // Moves the value's sign bit to the top and back, extending it over the upper bytes
+        let shift = 64 - 8 * ty.size as u32;
+        let signed = ((unsigned << shift) as i64) >> shift;
+        match ty.kind {
+            TypeKind::Signed => Value::Signed(signed),
+            TypeKind::Unsigned => Value::Unsigned(unsigned),
+            TypeKind::Char { signed: true } => Value::Char(signed),
+            TypeKind::Char { signed: false } => Value::Char(unsigned as i64),
+            TypeKind::Bool => Value::Bool(unsigned != 0),
+            TypeKind::Float => match bytes.len() {
+                4 => Value::Float(f32::from_le_bytes(bytes.try_into().unwrap()).into()),
+                8 => Value::Float(f64::from_le_bytes(bytes.try_into().unwrap())),
+                _ => Value::Unsupported(ty.name.clone()),
+            },
+            TypeKind::Pointer(_) => Value::Pointer(unsigned as usize),
+            TypeKind::Other => Value::Unsupported(ty.name.clone()),
+        }
+    }
+
+    /// The value as an integer, as a condition uses it. Pointers and strings are their address.
+    pub fn to_integer(&self) -> Option<i64> {
+        match *self {
+            Value::Signed(value) | Value::Char(value) => Some(value),
+            Value::Unsigned(value) => Some(value as i64),
+            Value::Bool(value) => Some(value as i64),
+            Value::Pointer(addr) | Value::String { addr, .. } => Some(addr as i64),
+            Value::Float(_) | Value::Unsupported(_) => None,
+        }
+    }
+}
+
+fn escape(bytes: &[u8]) -> String {
+    bytes
+        .iter()
+        .flat_map(|&byte| ascii::escape_default(byte))
+        .map(char::from)
+        .collect()
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Value::Signed(value) => write!(f, "{}", value),
+            Value::Unsigned(value) => write!(f, "{}", value),
+            Value::Char(value) => write!(f, "{} '{}'", value, escape(&[*value as u8])),
+            Value::Bool(value) => write!(f, "{}", value),
+            Value::Float(value) => write!(f, "{}", value),
+            Value::Pointer(addr) => write!(f, "{:#x}", addr),
+            Value::String {
+                addr,
+                text,
+                truncated,
+            } => write!(
+                f,
+                "{:#x} \"{}\"{}",
+                addr,
+                escape(text),
+                if *truncated { "..." } else { "" }
+            ),
+            Value::Unsupported(type_name) => write!(f, "<{} value>", type_name),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn decode(kind: TypeKind, bytes: &[u8]) -> Value {
+        Value::decode(&Type::new("t".to_string(), bytes.len(), kind), bytes)
+    }
+
+    #[test]
+    fn test_decode_integers() {
+        assert_eq!(
+            decode(TypeKind::Signed, &(-7i32).to_le_bytes()),
+            Value::Signed(-7)
+        );
+        assert_eq!(
+            decode(TypeKind::Signed, &(-2i16).to_le_bytes()),
+            Value::Signed(-2)
+        );
+        assert_eq!(
+            decode(TypeKind::Unsigned, &u64::MAX.to_le_bytes()),
+            Value::Unsigned(u64::MAX)
+        );
+        assert_eq!(
+            decode(TypeKind::Unsigned, &0xfffe_u16.to_le_bytes()),
+            Value::Unsigned(0xfffe)
+        );
+        assert_eq!(decode(TypeKind::Bool, &[2]), Value::Bool(true));
+        assert_eq!(
+            decode(TypeKind::Float, &1.5f32.to_le_bytes()),
+            Value::Float(1.5)
+        );
+    }
+
+    #[test]
+    fn test_decode_chars() {
+        assert_eq!(
+            decode(TypeKind::Char { signed: true }, &[0xff]),
+            Value::Char(-1)
+        );
+        assert_eq!(
+            decode(TypeKind::Char { signed: false }, &[0xff]),
+            Value::Char(255)
+        );
+        assert_eq!(
+            decode(TypeKind::Char { signed: true }, b"x").to_string(),
+            "120 'x'"
+        );
+        assert_eq!(
+            decode(TypeKind::Char { signed: true }, b"\n").to_string(),
+            "10 '\\n'"
+        );
+    }
+
+    #[test]
+    fn test_decode_unsupported() {
+        // A struct, and a value whose bytes don't match its type's size
+        assert_eq!(
+            decode(TypeKind::Other, &[0; 16]),
+            Value::Unsupported("t".to_string())
+        );
+        let int = Type::new("int".to_string(), 4, TypeKind::Signed);
+        assert_eq!(Value::decode(&int, &[0; 2]).to_string(), "<int value>");
+    }
+
+    #[test]
+    fn test_display_pointers() {
+        assert_eq!(
+            decode(TypeKind::Pointer(None), &0x404020usize.to_le_bytes()).to_string(),
+            "0x404020"
+        );
+        assert_eq!(Value::Pointer(0).to_string(), "0x0");
+        let string = Value::String {
+            addr: 0x402004,
+            text: b"say \"hi\"".to_vec(),
+            truncated: false,
+        };
+        assert_eq!(string.to_string(), "0x402004 \"say \\\"hi\\\"\"");
+        let long = Value::String {
+            addr: 0x402004,
+            text: b"aaa".to_vec(),
+            truncated: true,
+        };
+        assert_eq!(long.to_string(), "0x402004 \"aaa\"...");
+    }
+}
@@ -0,0 +1,745 @@
+use deet_core::{Event, LocationSpec, Session, Signal, Value};
+use errors::Error;
+use std::fs;
+use std::os::unix::fs::FileExt;
+use std::process::{Child, Command, Stdio};
+use std::thread;
+use std::time::Duration;
+
+fn sample(name: &str) -> Session {
+    let path = format!("{}/../deet/samples/{}", env!("CARGO_MANIFEST_DIR"), name);
+    Session::new(&path).unwrap_or_else(|err| {
+        panic!(
+            "Could not load {}: {}. Have you run make in proj-1/deet?",
+            path, err
+        )
+    })
+}
+
+fn next_event(session: &mut Session) -> Event {
+    let mut events: Vec<Event> = session.events().collect();
+    assert_eq!(events.len(), 1, "expected exactly one event: {:?}", events);
+    events.pop().unwrap()
+}
+
+#[test]
+fn test_run_to_exit() {
+    let mut session = sample("exit");
+    session.launch(&[]).unwrap();
+    assert!(matches!(next_event(&mut session), Event::Exited(0)));
+    assert!(!session.is_running());
+}
+
+#[test]
+fn test_segfault_stops_target() {
+    let mut session = sample("segfault");
+    session.launch(&[]).unwrap();
+    match next_event(&mut session) {
+        Event::Stopped { signal, rip } => {
+            assert_eq!(signal, Signal::SIGSEGV);
+            assert_eq!(
+                session.debug_data().get_function_from_addr(rip).as_deref(),
+                Some("func2")
+            );
+        }
+        other => panic!("expected the target to stop, got {:?}", other),
+    }
+
+    let functions: Vec<Option<String>> = session
+        .backtrace()
+        .unwrap()
+        .into_iter()
+        .map(|frame| frame.function)
+        .collect();
+    assert_eq!(
+        functions,
+        vec![
+            Some("func2".to_string()),
+            Some("func1".to_string()),
+            Some("main".to_string())
+        ]
+    );
+
+    session.kill().unwrap();
+    assert!(matches!(
+        next_event(&mut session),
+        Event::Signaled(Signal::SIGKILL)
+    ));
+}
+
+#[test]
+fn test_breakpoints() {
+    let mut session = sample("function_calls");
+    let func3 = session
+        .debug_data()
+        .get_addr_for_function(None, "func3")
+        .unwrap();
+    assert_eq!(
+        session
+            .set_breakpoint(LocationSpec::Function("func3".to_string()))
+            .unwrap(),
+        0
+    );
+    assert_eq!(session.breakpoints()[0].addr, func3);
+    session.launch(&[]).unwrap();
+
+    // func3 is called twice; the second hit proves the breakpoint was reinstalled after resuming
+    for _ in 0..2 {
+        match next_event(&mut session) {
+            Event::BreakpointHit { id, addr } => {
+                assert_eq!(id, 0);
+                assert_eq!(addr, func3);
+            }
+            other => panic!("expected a breakpoint hit, got {:?}", other),
+        }
+        session.resume().unwrap();
+    }
+    assert!(matches!(next_event(&mut session), Event::Exited(0)));
+}
+
+#[test]
+fn test_breakpoint_locations() {
+    let mut session = sample("segfault");
+    let func2 = session
+        .debug_data()
+        .get_addr_for_function(None, "func2")
+        .unwrap();
+    for location in &["func2", "segfault.c:3", &format!("*{:#x}", func2)] {
+        let index = session.set_breakpoint(location.parse().unwrap()).unwrap();
+        assert_eq!(session.breakpoints()[index].addr, func2, "{}", location);
+        assert_eq!(session.breakpoints()[index].location.to_string(), *location);
+    }
+
+    for location in &["no_such_function", "segfault.c:1000", "nosuchfile.c:3"] {
+        assert!(
+            matches!(
+                session.set_breakpoint(location.parse().unwrap()),
+                Err(Error::Parse(_))
+            ),
+            "{} should not resolve",
+            location
+        );
+    }
+    assert_eq!(session.breakpoints().len(), 3);
+}
+
+fn function(name: &str) -> LocationSpec {
+    LocationSpec::Function(name.to_string())
+}
+
+fn expect_hit(session: &mut Session, expected_id: usize) {
+    match next_event(session) {
+        Event::BreakpointHit { id, .. } => assert_eq!(id, expected_id),
+        other => panic!("expected breakpoint {}, got {:?}", expected_id, other),
+    }
+}
+
+#[test]
+fn test_disable_and_enable_breakpoint() {
+    let mut session = sample("function_calls");
+    let id = session.set_breakpoint(function("func3")).unwrap();
+    session.launch(&[]).unwrap();
+    expect_hit(&mut session, id);
+    let orig_byte = session.breakpoints()[0].original_byte();
+    assert!(orig_byte.is_some() && orig_byte != Some(0xcc));
+
+    // Disabling while stopped at the breakpoint puts the instruction back, so the second call
+    // to func3 doesn't stop
+    session.disable_breakpoint(id).unwrap();
+    assert!(!session.breakpoints()[0].enabled);
+    assert!(!session.breakpoints()[0].is_installed());
+    session.resume().unwrap();
+    assert!(matches!(next_event(&mut session), Event::Exited(0)));
+
+    // Disabled breakpoints aren't installed by the next launch either
+    session.launch(&[]).unwrap();
+    assert!(matches!(next_event(&mut session), Event::Exited(0)));
+
+    session.enable_breakpoint(id).unwrap();
+    session.launch(&[]).unwrap();
+    expect_hit(&mut session, id);
+    session.kill().unwrap();
+}
+
+#[test]
+fn test_delete_breakpoint() {
+    let mut session = sample("function_calls");
+    let func1 = session.set_breakpoint(function("func1")).unwrap();
+    let func3 = session.set_breakpoint(function("func3")).unwrap();
+    session.launch(&[]).unwrap();
+    expect_hit(&mut session, func1);
+
+    session.delete_breakpoint(func3).unwrap();
+    session.delete_breakpoint(func1).unwrap();
+    assert!(session.breakpoints().is_empty());
+    assert!(matches!(
+        session.delete_breakpoint(func1),
+        Err(Error::Parse(_))
+    ));
+    session.resume().unwrap();
+    assert!(matches!(next_event(&mut session), Event::Exited(0)));
+
+    // Ids aren't reused
+    assert_eq!(session.set_breakpoint(function("main")).unwrap(), 2);
+}
+
+#[test]
+fn test_breakpoints_sharing_an_address() {
+    let mut session = sample("function_calls");
+    let first = session.set_breakpoint(function("func3")).unwrap();
+    session.launch(&[]).unwrap();
+    expect_hit(&mut session, first);
+
+    // The second breakpoint shares the first one's trap, so removing either leaves the other
+    // working
+    let second = session.set_breakpoint(function("func3")).unwrap();
+    assert_eq!(
+        session.breakpoints()[1].original_byte(),
+        session.breakpoints()[0].original_byte()
+    );
+    session.delete_breakpoint(first).unwrap();
+    session.resume().unwrap();
+    expect_hit(&mut session, second);
+    session.resume().unwrap();
+    assert!(matches!(next_event(&mut session), Event::Exited(0)));
+}
+
+#[test]
+fn test_not_running() {
+    let mut session = sample("exit");
+    assert!(matches!(session.resume(), Err(Error::NotRunning)));
+    assert!(matches!(session.backtrace(), Err(Error::NotRunning)));
+    assert!(matches!(session.kill(), Err(Error::NotRunning)));
+    assert_eq!(session.events().count(), 0);
+}
+
+#[test]
+fn test_missing_target() {
+    assert!(matches!(
+        Session::new("/this/file/does/not/exist"),
+        Err(Error::Io { .. })
+    ));
+}
+
+/// Steps once and returns the source line the inferior stopped at.
+fn stepped_to(session: &mut Session, step: fn(&mut Session) -> errors::Result<()>) -> usize {
+    step(session).unwrap();
+    match next_event(session) {
+        Event::Stepped { rip } => session.debug_data().get_line_from_addr(rip).unwrap().number,
+        other => panic!("expected a step to complete, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_next_steps_over_calls() {
+    let mut session = sample("function_calls");
+    let id = session.set_breakpoint(function("func1")).unwrap();
+    session.launch(&[]).unwrap();
+    expect_hit(&mut session, id);
+
+    let lines: Vec<usize> = (0..5)
+        .map(|_| stepped_to(&mut session, Session::step_over_calls))
+        .collect();
+    assert_eq!(lines, vec![17, 18, 19, 20, 21]);
+    // Leaving func1 lands mid-way through line 24, so stepping carries on to the next line
+    assert_eq!(stepped_to(&mut session, Session::step_over_calls), 25);
+    session.step_over_calls().unwrap();
+    assert!(matches!(next_event(&mut session), Event::Exited(0)));
+}
+
+#[test]
+fn test_step_into_and_finish() {
+    let mut session = sample("function_calls");
+    let id = session
+        .set_breakpoint(LocationSpec::Line {
+            file: None,
+            line: 18,
+        })
+        .unwrap();
+    session.launch(&[]).unwrap();
+    expect_hit(&mut session, id);
+
+    // The first line of a function we step into is the one after its opening brace
+    assert_eq!(stepped_to(&mut session, Session::step), 10);
+    let rip = session.backtrace().unwrap()[0].rip;
+    assert_eq!(
+        session.debug_data().get_function_from_addr(rip).as_deref(),
+        Some("func2")
+    );
+    assert_eq!(stepped_to(&mut session, Session::step), 11);
+
+    // The call to func2 is the last instruction of line 18, so finish returns to the start of
+    // line 19
+    assert_eq!(stepped_to(&mut session, Session::finish), 19);
+    let functions: Vec<Option<String>> = session
+        .backtrace()
+        .unwrap()
+        .into_iter()
+        .map(|frame| frame.function)
+        .collect();
+    assert_eq!(
+        functions,
+        vec![Some("func1".to_string()), Some("main".to_string())]
+    );
+    assert_eq!(stepped_to(&mut session, Session::step_over_calls), 20);
+    session.kill().unwrap();
+}
+
+#[test]
+fn test_finish_from_function_entry() {
+    // Stopped on a function's first instruction, before its prologue has run
+    let mut session = sample("function_calls");
+    let id = session.set_breakpoint(function("func3")).unwrap();
+    session.launch(&[]).unwrap();
+    expect_hit(&mut session, id);
+    assert_eq!(stepped_to(&mut session, Session::finish), 14);
+    session.kill().unwrap();
+}
+
+#[test]
+fn test_step_stops_at_breakpoints() {
+    let mut session = sample("function_calls");
+    let func1 = session.set_breakpoint(function("func1")).unwrap();
+    let func3 = session.set_breakpoint(function("func3")).unwrap();
+    session.launch(&[]).unwrap();
+    expect_hit(&mut session, func1);
+
+    // Stepping over the call on line 18 still stops at the breakpoint in func3 that func2 hits
+    assert_eq!(stepped_to(&mut session, Session::step_over_calls), 17);
+    assert_eq!(stepped_to(&mut session, Session::step_over_calls), 18);
+    session.step_over_calls().unwrap();
+    expect_hit(&mut session, func3);
+    session.kill().unwrap();
+}
+
+#[test]
+fn test_read_variables() {
+    let mut session = sample("variables");
+    assert!(matches!(
+        session.read_variable("counter"),
+        Err(Error::NotRunning)
+    ));
+    let id = session
+        .set_breakpoint(LocationSpec::Line {
+            file: Some("variables.c".to_string()),
+            line: 10,
+        })
+        .unwrap();
+    session.launch(&[]).unwrap();
+    expect_hit(&mut session, id);
+
+    let locals: Vec<(String, String)> = session
+        .locals()
+        .unwrap()
+        .into_iter()
+        .map(|(name, value)| (name, value.unwrap().to_string()))
+        .collect();
+    let names: Vec<&str> = locals.iter().map(|(name, _)| name.as_str()).collect();
+    assert_eq!(
+        names,
+        vec!["label", "value", "big", "initial", "doubled", "ptr"]
+    );
+    assert!(locals[0].1.ends_with(" \"label\""), "{}", locals[0].1);
+    assert_eq!(session.read_variable("value").unwrap(), Value::Signed(-7));
+    assert_eq!(
+        session.read_variable("big").unwrap(),
+        Value::Unsigned(u64::MAX)
+    );
+    assert_eq!(
+        session.read_variable("initial").unwrap(),
+        Value::Char(b'x' as i64)
+    );
+    assert_eq!(
+        session.read_variable("doubled").unwrap(),
+        Value::Signed(-14)
+    );
+    assert!(matches!(
+        session.read_variable("ptr").unwrap(),
+        Value::Pointer(addr) if addr != 0
+    ));
+
+    // Globals
+    assert_eq!(session.read_variable("counter").unwrap(), Value::Signed(3));
+    match session.read_variable("greeting").unwrap() {
+        Value::String {
+            text, truncated, ..
+        } => {
+            assert_eq!(text, b"hello, world");
+            assert!(!truncated);
+        }
+        other => panic!("expected a string, got {:?}", other),
+    }
+    assert_eq!(session.read_variable("nothing").unwrap(), Value::Pointer(0));
+    assert!(matches!(
+        session.read_variable("negative"),
+        Err(Error::Parse(_))
+    ));
+
+    // Back in main, its own local is visible and the global has been updated
+    assert_eq!(stepped_to(&mut session, Session::finish), 16);
+    assert_eq!(
+        session.read_variable("negative").unwrap(),
+        Value::Signed(-7)
+    );
+    assert_eq!(stepped_to(&mut session, Session::step_over_calls), 17);
+    assert_eq!(session.read_variable("counter").unwrap(), Value::Signed(4));
+    session.kill().unwrap();
+}
+
+fn square_body() -> LocationSpec {
+    // The line after square's prologue, where its parameter has been stored
+    LocationSpec::Line {
+        file: Some("loop.c".to_string()),
+        line: 4,
+    }
+}
+
+#[test]
+fn test_conditional_breakpoint() {
+    let mut session = sample("loop");
+    let id = session.set_breakpoint(square_body()).unwrap();
+    session
+        .set_condition(id, Some("n > 6 && n % 2 == 1".parse().unwrap()))
+        .unwrap();
+    session.launch(&[]).unwrap();
+    for n in &[7, 9] {
+        expect_hit(&mut session, id);
+        assert_eq!(session.read_variable("n").unwrap(), Value::Signed(*n));
+        session.resume().unwrap();
+    }
+    assert!(matches!(next_event(&mut session), Event::Exited(0)));
+    assert_eq!(session.breakpoints()[0].hit_count, 2);
+
+    // Hit counts start again with each run
+    session.set_condition(id, None).unwrap();
+    session.launch(&[]).unwrap();
+    expect_hit(&mut session, id);
+    assert_eq!(session.breakpoints()[0].hit_count, 1);
+    session.kill().unwrap();
+}
+
+#[test]
+fn test_ignore_count() {
+    let mut session = sample("loop");
+    let id = session.set_breakpoint(square_body()).unwrap();
+    session.set_ignore_count(id, 3).unwrap();
+    session.launch(&[]).unwrap();
+    expect_hit(&mut session, id);
+    // Ignored hits are counted too
+    assert_eq!(session.read_variable("n").unwrap(), Value::Signed(3));
+    assert_eq!(session.breakpoints()[0].hit_count, 4);
+    assert_eq!(session.breakpoints()[0].ignore_count, 0);
+
+    // A breakpoint being ignored doesn't stop a step over a call either
+    let main_loop = session
+        .set_breakpoint(LocationSpec::Line {
+            file: Some("loop.c".to_string()),
+            line: 10,
+        })
+        .unwrap();
+    session.resume().unwrap();
+    expect_hit(&mut session, main_loop);
+    session.set_ignore_count(id, 1).unwrap();
+    assert_eq!(stepped_to(&mut session, Session::step_over_calls), 9);
+    assert_eq!(session.breakpoints()[0].hit_count, 5);
+    session.kill().unwrap();
+}
+
+#[test]
+fn test_condition_errors_stop() {
+    let mut session = sample("loop");
+    let id = session.set_breakpoint(square_body()).unwrap();
+    session
+        .set_condition(id, Some("no_such_variable == 1".parse().unwrap()))
+        .unwrap();
+    session.launch(&[]).unwrap();
+    let events: Vec<Event> = session.events().collect();
+    assert!(
+        matches!(
+            events.as_slice(),
+            [Event::Warning(Error::Parse(_)), Event::BreakpointHit { .. }]
+        ),
+        "{:?}",
+        events
+    );
+    assert!(matches!(
+        session.set_condition(99, None),
+        Err(Error::Parse(_))
+    ));
+    session.kill().unwrap();
+}
+
+/// Starts a sample as an ordinary, untraced child, and waits until it is running its own code.
+fn spawn_untraced(name: &str, args: &[&str]) -> Child {
+    let path = format!("{}/../deet/samples/{}", env!("CARGO_MANIFEST_DIR"), name);
+    let child = Command::new(&path)
+        .args(args)
+        .stdout(Stdio::null())
+        .spawn()
+        .unwrap();
+    let exe = format!("/proc/{}/exe", child.id());
+    while !fs::read_link(&exe).is_ok_and(|exe| exe.ends_with(name)) {
+        thread::sleep(Duration::from_millis(10));
+    }
+    child
+}
+
+#[test]
+fn test_attach_and_detach() {
+    let mut child = spawn_untraced("sleepy_print", &["2"]);
+    let mut session = Session::attach_to(child.id() as i32).unwrap();
+    assert!(session.is_attached());
+    assert_eq!(session.pid(), Some(child.id() as i32));
+    assert!(session.target().ends_with("sleepy_print"));
+    assert!(matches!(
+        next_event(&mut session),
+        Event::Stopped {
+            signal: Signal::SIGSTOP,
+            ..
+        }
+    ));
+
+    let id = session
+        .set_breakpoint(LocationSpec::Line {
+            file: Some("sleepy_print.c".to_string()),
+            line: 12,
+        })
+        .unwrap();
+    session.resume().unwrap();
+    expect_hit(&mut session, id);
+
+    // Detaching takes the trap out, so the process goes round its loop again without dying of
+    // SIGTRAP
+    session.detach().unwrap();
+    assert!(!session.is_running());
+    assert!(!session.breakpoints()[0].is_installed());
+    assert!(matches!(session.detach(), Err(Error::NotRunning)));
+    assert!(child.wait().unwrap().success());
+}
+
+#[test]
+fn test_attach_resolves_breakpoints_again() {
+    let mut session = sample("function_calls");
+    session.set_breakpoint(function("main")).unwrap();
+    session.set_breakpoint(function("func1")).unwrap();
+    let mut child = spawn_untraced("sleepy_print", &["1"]);
+    session.attach(child.id() as i32).unwrap();
+
+    // sleepy_print has a main, but no func1
+    let events: Vec<Event> = session.events().collect();
+    assert!(
+        matches!(
+            events.as_slice(),
+            [Event::Warning(Error::Parse(_)), Event::Stopped { .. }]
+        ),
+        "{:?}",
+        events
+    );
+    let main = session
+        .debug_data()
+        .get_addr_for_function(None, "main")
+        .unwrap();
+    assert_eq!(session.breakpoints()[0].addr, main);
+    assert!(session.breakpoints()[0].is_installed());
+    assert!(!session.breakpoints()[1].enabled);
+
+    // Letting go of a process we attached to detaches from it rather than killing it
+    session.release().unwrap();
+    assert!(session.events().next().is_none());
+    assert!(child.wait().unwrap().success());
+}
+
+#[test]
+fn test_enable_breakpoint_that_no_longer_resolves() {
+    let mut session = sample("function_calls");
+    let id = session.set_breakpoint(function("func1")).unwrap();
+    let old_addr = session.breakpoints()[0].addr;
+    let mut child = spawn_untraced("sleepy_print", &["1"]);
+    session.attach(child.id() as i32).unwrap();
+    session.events().for_each(drop);
+    assert!(!session.breakpoints()[0].enabled);
+
+    // func1's address in function_calls is just some instruction in sleepy_print, which enabling
+    // the breakpoint mustn't overwrite
+    let mem = fs::File::open(format!("/proc/{}/mem", child.id())).unwrap();
+    let read_byte = || {
+        let mut byte = [0];
+        mem.read_exact_at(&mut byte, old_addr as u64).unwrap();
+        byte[0]
+    };
+    let before = read_byte();
+    assert!(matches!(
+        session.enable_breakpoint(id),
+        Err(Error::Parse(_))
+    ));
+    assert_eq!(read_byte(), before);
+    assert!(!session.breakpoints()[0].enabled);
+    assert!(!session.breakpoints()[0].is_installed());
+
+    session.release().unwrap();
+    assert!(child.wait().unwrap().success());
+}
+
+#[test]
+fn test_attach_lets_new_process_go_if_release_fails() {
+    let mut old = spawn_untraced("sleepy_print", &["5"]);
+    let mut session = Session::attach_to(old.id() as i32).unwrap();
+    session.set_breakpoint(function("main")).unwrap();
+    // With the old process gone, its breakpoint can't be taken out, so releasing it fails
+    old.kill().unwrap();
+    old.wait().unwrap();
+
+    let mut new = spawn_untraced("sleepy_print", &["1"]);
+    assert!(session.attach(new.id() as i32).is_err());
+    let status = fs::read_to_string(format!("/proc/{}/status", new.id())).unwrap();
+    assert!(status.contains("TracerPid:\t0\n"), "{}", status);
+    assert!(new.wait().unwrap().success());
+}
+
+fn worker_body() -> LocationSpec {
+    LocationSpec::Line {
+        file: Some("threads.c".to_string()),
+        line: 12,
+    }
+}
+
+#[test]
+fn test_threads_hit_breakpoints() {
+    let mut session = sample("threads");
+    let id = session.set_breakpoint(worker_body()).unwrap();
+    session.launch(&[]).unwrap();
+
+    // Each worker hits the breakpoint once, in its own thread, which is selected when it stops
+    let mut hit_threads = Vec::new();
+    for _ in 0..3 {
+        expect_hit(&mut session, id);
+        let selected = session.selected_thread().unwrap();
+        assert_ne!(selected, 1);
+        assert!(!hit_threads.contains(&selected));
+        hit_threads.push(selected);
+
+        let threads = session.threads().unwrap();
+        assert!(threads.len() >= 2, "{:?}", threads);
+        assert_eq!(threads[0].id, 1);
+        assert_eq!(Some(threads[0].tid), session.pid());
+        let current = threads.iter().find(|thread| thread.selected).unwrap();
+        assert_eq!(current.id, selected);
+        assert_eq!(current.frame.function.as_deref(), Some("worker"));
+        assert_eq!(
+            session.backtrace().unwrap()[0].function.as_deref(),
+            Some("worker")
+        );
+        session.resume().unwrap();
+    }
+    assert!(matches!(next_event(&mut session), Event::Exited(0)));
+    assert!(session.threads().is_err());
+}
+
+#[test]
+fn test_select_thread() {
+    let mut session = sample("threads");
+    let id = session.set_breakpoint(worker_body()).unwrap();
+    session.launch(&[]).unwrap();
+    expect_hit(&mut session, id);
+    let worker = session.selected_thread().unwrap();
+    let index = session.read_variable("index").unwrap();
+
+    // The main thread is somewhere in pthread_create or pthread_join, without line information
+    session.select_thread(1).unwrap();
+    assert_eq!(session.selected_thread(), Some(1));
+    assert_ne!(
+        session.backtrace().unwrap()[0].function.as_deref(),
+        Some("worker")
+    );
+    assert!(matches!(session.select_thread(99), Err(Error::Parse(_))));
+    assert_eq!(session.selected_thread(), Some(1));
+
+    // Stepping only moves the selected thread
+    session.select_thread(worker).unwrap();
+    assert_eq!(stepped_to(&mut session, Session::step_over_calls), 13);
+    assert_eq!(session.selected_thread(), Some(worker));
+    assert_eq!(session.read_variable("index").unwrap(), index);
+
+    // Having selected another thread doesn't make the one that hit the breakpoint report it
+    // again; the other two workers each do, and then the target finishes
+    session.select_thread(1).unwrap();
+    for _ in 0..2 {
+        session.resume().unwrap();
+        expect_hit(&mut session, id);
+        assert_ne!(session.selected_thread(), Some(worker));
+    }
+    session.resume().unwrap();
+    assert!(matches!(next_event(&mut session), Event::Exited(0)));
+}
+
+#[test]
+fn test_attach_to_threads() {
+    let mut child = spawn_untraced("threads", &["1"]);
+    let tasks = format!("/proc/{}/task", child.id());
+    while fs::read_dir(&tasks).unwrap().count() < 4 {
+        thread::sleep(Duration::from_millis(10));
+    }
+    let mut session = Session::attach_to(child.id() as i32).unwrap();
+    next_event(&mut session);
+    assert_eq!(session.threads().unwrap().len(), 4);
+
+    let id = session.set_breakpoint(worker_body()).unwrap();
+    session.resume().unwrap();
+    expect_hit(&mut session, id);
+    assert_ne!(session.selected_thread(), Some(1));
+
+    // Detaching lets every thread carry on, the others having been stopped along the way
+    session.detach().unwrap();
+    assert!(child.wait().unwrap().success());
+}
+
+#[test]
+fn test_source_lines() {
+    let mut session = sample("loop");
+    // Before the target runs, listing starts at main
+    let (path, line) = session.source_line(None).unwrap();
+    assert!(path.ends_with("samples/loop.c"), "{}", path);
+    assert_eq!(line, 7);
+    assert_eq!(
+        session.source_line(Some(&function("square"))).unwrap(),
+        (path.clone(), 3)
+    );
+    // A line is taken as it is, code or not
+    let blank = LocationSpec::Line {
+        file: Some("loop.c".to_string()),
+        line: 6,
+    };
+    assert_eq!(
+        session.source_line(Some(&blank)).unwrap(),
+        (path.clone(), 6)
+    );
+    let missing = LocationSpec::Line {
+        file: Some("nope.c".to_string()),
+        line: 1,
+    };
+    assert!(matches!(
+        session.source_line(Some(&missing)),
+        Err(Error::Parse(_))
+    ));
+
+    assert_eq!(
+        session.source_lines(&path, 3, 2).unwrap(),
+        vec![
+            (3, "int square(int n) {".to_string()),
+            (4, "    return n * n;".to_string())
+        ]
+    );
+    assert_eq!(session.source_lines(&path, 13, 10).unwrap().len(), 2);
+    assert!(session.source_lines(&path, 15, 10).unwrap().is_empty());
+    assert!(matches!(
+        session.source_lines("/nonexistent/loop.c", 1, 10),
+        Err(Error::Io { .. })
+    ));
+
+    // Once it runs, the default is where it is stopped
+    let id = session.set_breakpoint(square_body()).unwrap();
+    session.launch(&[]).unwrap();
+    expect_hit(&mut session, id);
+    assert_eq!(session.source_line(None).unwrap(), (path, 4));
+    session.kill().unwrap();
+}
@@ -0,0 +1,14 @@
+#include <stdio.h>
+
+int square(int n) {
+    return n * n;
+}
+
+int main() {
+    int total = 0;
+    for (int i = 0; i < 10; i++) {
+        total += square(i);
+    }
+    printf("%d\n", total);
+    return 0;
+}
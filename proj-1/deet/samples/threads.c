@@ -0,0 +1,31 @@
+#include <pthread.h>
+#include <stdio.h>
+#include <stdlib.h>
+#include <unistd.h>
+
+int results[3];
+int delay = 0;
+
+void *worker(void *arg) {
+    int index = *(int *)arg;
+    sleep(delay);
+    results[index] = (index + 1) * 10;
+    return NULL;
+}
+
+int main(int argc, char *argv[]) {
+    if (argc > 1) {
+        delay = atoi(argv[1]);
+    }
+    pthread_t threads[3];
+    int indices[3];
+    for (int i = 0; i < 3; i++) {
+        indices[i] = i;
+        pthread_create(&threads[i], NULL, worker, &indices[i]);
+    }
+    for (int i = 0; i < 3; i++) {
+        pthread_join(threads[i], NULL);
+    }
+    printf("%d %d %d\n", results[0], results[1], results[2]);
+    return 0;
+}
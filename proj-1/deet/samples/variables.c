@@ -0,0 +1,19 @@
+#include <stdio.h>
+
+int counter = 3;
+const char *greeting = "hello, world";
+char *nothing = NULL;
+
+void show(const char *label, int value, unsigned long big, char initial) {
+    int doubled = value * 2;
+    int *ptr = &doubled;
+    printf("%s %d %lu %c %d\n", label, value, big, initial, *ptr);
+}
+
+int main() {
+    int negative = -7;
+    show("label", negative, 18446744073709551615UL, 'x');
+    counter++;
+    printf("%s %d\n", greeting, counter);
+    return 0;
+}
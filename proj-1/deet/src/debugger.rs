@@ -1,41 +1,36 @@
-use std::ops::RangeBounds;
-
-use crate::debugger_command::DebuggerCommand;
-use crate::dwarf_data::{DwarfData, Error as DwarfError};
-use crate::inferior::Inferior;
-use crate::inferior::Status;
-use libc::ptrace;
-use nix::sys::ptrace;
+use deet::debugger_command::DebuggerCommand;
+use deet_core::{Condition, Event, LocationSpec, Session};
+use errors::Error;
 use rustyline::error::ReadlineError;
 use rustyline::Editor;
 
+/// How many source lines `list` shows at a time.
+const LIST_LINES: usize = 10;
+
 pub struct Debugger {
-    target: String,
     history_path: String,
     readline: Editor<()>,
-    inferior: Option<Inferior>,
-    debug_data: DwarfData,
-    breakpoints: Vec<usize>,
+    session: Session,
+    /// The file last listed and the line after the listing, where another `list` carries on
+    /// from. Cleared when the target stops somewhere new.
+    listed: Option<(String, usize)>,
 }
 
 impl Debugger {
     /// Initializes the debugger.
-    pub fn new(target: &str) -> Debugger {
-        let debug_data = match DwarfData::from_file(target) {
-            Ok(val) => val,
-            Err(DwarfError::ErrorOpeningFile) => {
-                println!("could not open file {}", target);
-                std::process::exit(1);
-            }
-            Err(DwarfError::DwarfFormatError(err)) => {
-                println!(
-                    "could not load debugging symbols from {}: {:?}",
-                    target, err
-                );
-                std::process::exit(1);
-            }
-        };
-        debug_data.print();
+    pub fn new(target: &str) -> errors::Result<Debugger> {
+        Ok(Debugger::with_session(Session::new(target)?))
+    }
+
+    /// Initializes the debugger attached to the running process `pid`.
+    pub fn attach(pid: i32) -> errors::Result<Debugger> {
+        let mut debugger = Debugger::with_session(Session::attach_to(pid)?);
+        debugger.report(Ok(()));
+        Ok(debugger)
+    }
+
+    fn with_session(session: Session) -> Debugger {
+        session.debug_data().print();
 
         let history_path = format!("{}/.deet_history", std::env::var("HOME").unwrap());
         let mut readline = Editor::<()>::new();
@@ -43,46 +38,185 @@ impl Debugger {
         let _ = readline.load_history(&history_path);
 
         Debugger {
-            target: target.to_string(),
             history_path,
             readline,
-            inferior: None,
-            debug_data,
-            breakpoints: Vec::new()
+            session,
+            listed: None,
         }
     }
 
-    pub fn parse_addr(&self, addr: &str) -> Option<usize> {
-        if addr.to_lowercase().starts_with("0x") {
-            // address
-            return usize::from_str_radix(&addr[2..], 16).ok();
-        } else if String::from(addr).parse::<usize>().is_ok() {
-            // line number
-            let line_num = String::from(addr).parse::<usize>().expect("can not parse line number");
-            return self.debug_data.get_addr_for_line(None, line_num);
-        } else {
-            // function name
-            return self.debug_data.get_addr_for_function(None, addr);
+    /// Describes where `addr` is in the source, e.g. "main (samples/exit.c:4)".
+    fn describe(&self, addr: usize) -> String {
+        let debug_data = self.session.debug_data();
+        format!(
+            "{} ({})",
+            debug_data
+                .get_function_from_addr(addr)
+                .unwrap_or_else(|| "??".to_string()),
+            debug_data
+                .get_line_from_addr(addr)
+                .map_or_else(|| "??".to_string(), |line| line.to_string())
+        )
+    }
+
+    /// Says which thread stopped, e.g. "thread 2 ", once the target has more than one.
+    fn stopped_thread(&self) -> String {
+        match (self.session.threads(), self.session.selected_thread()) {
+            (Ok(threads), Some(id)) if threads.len() > 1 => format!("thread {} ", id),
+            _ => String::new(),
         }
     }
 
-    pub fn print_status(&self, status: Status) {
-        match status {
-            Status::Exited(exit_code) => {
-                println!("target exited (status {})", exit_code);
-            }
-            Status::Signaled(signal) => {
-                println!("target signaled(killed) by {}", signal.as_str());
+    pub fn print_event(&self, event: Event) {
+        match event {
+            Event::BreakpointHit { id, addr } => {
+                println!(
+                    "target {}stopped at breakpoint {} ({:#x}) in {}",
+                    self.stopped_thread(),
+                    id,
+                    addr,
+                    self.describe(addr)
+                );
             }
-            Status::Stopped(signal, rip) => {
+            Event::Stopped { signal, rip } => {
                 println!(
-                    "target stopped at {:#x} by signal {} in {} ({})",
+                    "target {}stopped at {:#x} by signal {} in {}",
+                    self.stopped_thread(),
                     rip,
                     signal.as_str(),
-                    self.debug_data.get_function_from_addr(rip).unwrap(),
-                    self.debug_data.get_line_from_addr(rip).unwrap()
+                    self.describe(rip)
+                );
+            }
+            Event::Stepped { rip } => {
+                println!(
+                    "target {}stopped at {:#x} in {}",
+                    self.stopped_thread(),
+                    rip,
+                    self.describe(rip)
                 );
             }
+            Event::ThreadExited(id) => {
+                println!("thread {} exited", id);
+            }
+            Event::Exited(exit_code) => {
+                println!("target exited (status {})", exit_code);
+            }
+            Event::Signaled(signal) => {
+                println!("target signaled(killed) by {}", signal.as_str());
+            }
+            Event::Warning(err) => println!("{}", err),
+        }
+    }
+
+    fn print_breakpoints(&self) {
+        if self.session.breakpoints().is_empty() {
+            println!("no breakpoints");
+            return;
+        }
+        println!(
+            "{:<4} {:<8} {:<18} {:<24} {:<5} Where",
+            "Num", "Enabled", "Address", "Location", "Hits"
+        );
+        for bp in self.session.breakpoints() {
+            println!(
+                "{:<4} {:<8} {:<18} {:<24} {:<5} {}",
+                bp.id,
+                if bp.enabled { "y" } else { "n" },
+                format!("{:#x}", bp.addr),
+                bp.location.to_string(),
+                bp.hit_count,
+                self.describe(bp.addr)
+            );
+            if let Some(condition) = &bp.condition {
+                println!("\tstop only if {}", condition);
+            }
+            if bp.ignore_count > 0 {
+                println!("\twill ignore next {} hits", bp.ignore_count);
+            }
+        }
+    }
+
+    fn print_threads(&self) {
+        let threads = match self.session.threads() {
+            Ok(threads) => threads,
+            Err(err) => {
+                println!("{}", err);
+                return;
+            }
+        };
+        println!("  {:<4} {:<8} Where", "Id", "Tid");
+        for thread in threads {
+            println!(
+                "{} {:<4} {:<8} {:#x} {}",
+                if thread.selected { "*" } else { " " },
+                thread.id,
+                thread.tid,
+                thread.frame.rip,
+                self.describe(thread.frame.rip)
+            );
+        }
+    }
+
+    /// Lists source lines around `location`, or around where the target is stopped, or on from
+    /// the last listing if nothing has happened since. The line the target is stopped at is
+    /// marked.
+    fn list(&mut self, location: Option<&str>) -> errors::Result<()> {
+        let (path, first) = match (location, self.listed.take()) {
+            (None, Some(listed)) => listed,
+            _ => {
+                let location = location.map(str::parse::<LocationSpec>).transpose()?;
+                let (path, line) = self.session.source_line(location.as_ref())?;
+                (path, line.saturating_sub(LIST_LINES / 2).max(1))
+            }
+        };
+        let lines = self.session.source_lines(&path, first, LIST_LINES)?;
+        let last = match lines.last() {
+            Some((number, _)) => *number,
+            None => {
+                return Err(Error::Parse(format!(
+                    "line {} is past the end of {}",
+                    first, path
+                )))
+            }
+        };
+        let current = if self.session.is_running() {
+            self.session.source_line(None).ok()
+        } else {
+            None
+        };
+        for (number, text) in lines {
+            let marker = match &current {
+                Some((file, line)) if *file == path && *line == number => "=>",
+                _ => "  ",
+            };
+            println!("{} {:<4} {}", marker, number, text);
+        }
+        self.listed = Some((path, last + 1));
+        Ok(())
+    }
+
+    /// Sets a breakpoint at `location`, which only stops when `condition` holds if there is one.
+    /// The condition is parsed first, so a bad one doesn't leave a breakpoint behind.
+    fn set_breakpoint(&mut self, location: &str, condition: Option<&str>) -> errors::Result<usize> {
+        let location = location.parse::<LocationSpec>()?;
+        let condition = condition.map(str::parse::<Condition>).transpose()?;
+        let id = self.session.set_breakpoint(location)?;
+        self.session.set_condition(id, condition)?;
+        Ok(id)
+    }
+
+    /// Prints the outcome of a session operation: its error, if it failed, and whatever events it
+    /// produced either way.
+    fn report(&mut self, result: errors::Result<()>) {
+        if let Err(err) = result {
+            println!("{}", err);
+        }
+        let events: Vec<Event> = self.session.events().collect();
+        if !events.is_empty() {
+            self.listed = None;
+        }
+        for event in events {
+            self.print_event(event);
         }
     }
 
@@ -90,95 +224,130 @@ impl Debugger {
         loop {
             match self.get_next_command() {
                 DebuggerCommand::Run(args) => {
-                    // make sure no previous target exists
-                    if self.inferior.is_some() {
-                        match self.inferior.as_mut().unwrap().terminate() {
-                            Ok(status) => self.print_status(status),
-                            Err(err) => println!("failed to terminate previous target, {}", err),
-                        }
+                    let result = self.session.launch(&args);
+                    self.report(result);
+                }
+                DebuggerCommand::Attach(pid) => {
+                    let result = self.session.attach(pid);
+                    if result.is_ok() {
+                        println!("attached to process {} ({})", pid, self.session.target());
                     }
-
-                    if let Some(inferior) = Inferior::new(&self.target, &args, &self.breakpoints) {
-                        // Create the inferior
-                        self.inferior = Some(inferior);
-                        match self.inferior.as_mut().unwrap().cont() {
-                            Ok(status) => self.print_status(status),
-                            Err(err) => {
-                                println!("failed to run command, {}", err);
-                            }
-                        }
-                    } else {
-                        println!("Error starting subprocess");
+                    self.report(result);
+                }
+                DebuggerCommand::Detach => {
+                    let pid = self.session.pid();
+                    match self.session.detach() {
+                        Ok(()) => println!("detached from process {}", pid.unwrap()),
+                        Err(err) => println!("{}", err),
                     }
                 }
                 DebuggerCommand::Continue => {
-                    if self.inferior.is_none() {
-                        println!("please run target first");
-                        continue;
-                    }
-                    // check if inferior is stopped at a breakpoint
-                    let inf_ref = self.inferior.as_mut().unwrap();
-                    let mut regs = ptrace::getregs(inf_ref.pid()).expect("can not read registers");
-                    let rip = regs.rip - 1;
-                    if inf_ref.replaced_values.contains_key(&(rip as usize)) {
-                        // this is a breakpoint, resume original byte
-                        let val = inf_ref.replaced_values.get(&(rip as usize)).unwrap();
-                        let trap_byte = inf_ref.write_byte(rip as usize, *val).expect("can not resume original byte");
-                        if trap_byte != 0xcc {
-                            panic!("failed to resume original byte");
+                    let result = self.session.resume();
+                    self.report(result);
+                }
+                DebuggerCommand::Step => {
+                    let result = self.session.step();
+                    self.report(result);
+                }
+                DebuggerCommand::Next => {
+                    let result = self.session.step_over_calls();
+                    self.report(result);
+                }
+                DebuggerCommand::Finish => {
+                    let result = self.session.finish();
+                    self.report(result);
+                }
+                DebuggerCommand::BackTrace => match self.session.backtrace() {
+                    Ok(frames) => {
+                        for frame in frames {
+                            println!("%rip {:#x} {}", frame.rip, self.describe(frame.rip));
                         }
-                        regs.rip = rip;
-                        ptrace::setregs(inf_ref.pid(), regs).expect("can not set %rip");
-
-                        // step a intruction and reinstall breakpoint
-                        ptrace::step(inf_ref.pid(), None).expect("can not step target");
-                        inf_ref.wait(None).expect("can not stop after stepping");
-                        inf_ref.write_byte(rip as usize, 0xcc).expect("can not reinstall breakpoint");
                     }
-
-                    match self.inferior.as_mut().unwrap().cont() {
-                        Ok(status) => self.print_status(status),
-                        Err(err) => {
-                            println!("failed to run command, {}", err);
+                    Err(err) => println!("{}", err),
+                },
+                DebuggerCommand::Breakpoint {
+                    location,
+                    condition,
+                } => match self.set_breakpoint(&location, condition.as_deref()) {
+                    Ok(id) => {
+                        let addr = self.session.breakpoints().last().unwrap().addr;
+                        println!(
+                            "set breakpoint {} at {:#x}: {}",
+                            id,
+                            addr,
+                            self.describe(addr)
+                        )
+                    }
+                    Err(err) => println!("{}", err),
+                },
+                DebuggerCommand::InfoBreakpoints => self.print_breakpoints(),
+                DebuggerCommand::InfoLocals => match self.session.locals() {
+                    Ok(locals) if locals.is_empty() => println!("no locals"),
+                    Ok(locals) => {
+                        for (name, value) in locals {
+                            match value {
+                                Ok(value) => println!("{} = {}", name, value),
+                                Err(err) => println!("{} = <{}>", name, err),
+                            }
                         }
                     }
-                }
-                DebuggerCommand::BackTrace => {
-                    let _ = self
-                        .inferior
-                        .as_mut()
-                        .unwrap()
-                        .print_backtrace(&self.debug_data);
-                }
-                DebuggerCommand::Breakpoint(s) => {
-                    match self.parse_addr(&s) {
-                        Some(addr) => {
-                            self.breakpoints.push(addr);
-                            if self.inferior.is_some() {
-                                // inferior is running, add breakpoint
-                                match self.inferior.as_mut().unwrap().write_byte(addr, 0xcc) {
-                                    Ok(_) => {}
-                                    Err(err) => println!(
-                                        "failed to set breakpoint at position {:#x}, {}",
-                                        addr, err
-                                    ),
-                                }
+                    Err(err) => println!("{}", err),
+                },
+                DebuggerCommand::InfoThreads => self.print_threads(),
+                DebuggerCommand::Thread(id) => match self.session.select_thread(id) {
+                    Ok(()) => {
+                        self.listed = None;
+                        match self.session.threads() {
+                            Ok(threads) => {
+                                let thread = threads.iter().find(|thread| thread.selected).unwrap();
+                                println!(
+                                    "switched to thread {} (tid {}) at {:#x} in {}",
+                                    id,
+                                    thread.tid,
+                                    thread.frame.rip,
+                                    self.describe(thread.frame.rip)
+                                );
                             }
-                            println!(
-                                "set breakpoint {} at position {:#x}",
-                                self.breakpoints.len() - 1,
-                                addr
-                            );
+                            Err(err) => println!("{}", err),
                         }
-                        None => println!("invalid breakpoint format"),
-                    };
+                    }
+                    Err(err) => println!("{}", err),
+                },
+                DebuggerCommand::Print(name) => match self.session.read_variable(&name) {
+                    Ok(value) => println!("{} = {}", name, value),
+                    Err(err) => println!("{}", err),
+                },
+                DebuggerCommand::List(location) => {
+                    if let Err(err) = self.list(location.as_deref()) {
+                        println!("{}", err);
+                    }
+                }
+                DebuggerCommand::Delete(id) => {
+                    if let Err(err) = self.session.delete_breakpoint(id) {
+                        println!("{}", err);
+                    }
+                }
+                DebuggerCommand::Ignore(id, count) => {
+                    match self.session.set_ignore_count(id, count) {
+                        Ok(()) => println!("will ignore next {} hits of breakpoint {}", count, id),
+                        Err(err) => println!("{}", err),
+                    }
+                }
+                DebuggerCommand::Enable(id) => {
+                    if let Err(err) = self.session.enable_breakpoint(id) {
+                        println!("{}", err);
+                    }
+                }
+                DebuggerCommand::Disable(id) => {
+                    if let Err(err) = self.session.disable_breakpoint(id) {
+                        println!("{}", err);
+                    }
                 }
                 DebuggerCommand::Quit => {
-                    match self.inferior.as_mut().unwrap().terminate() {
-                        Ok(status) => self.print_status(status),
-                        Err(err) => {
-                            println!("failed to terminate target, {}", err);
-                        }
+                    // A process we attached to is detached from rather than killed
+                    if self.session.is_running() {
+                        let result = self.session.release();
+                        self.report(result);
                     }
                     return;
                 }
@@ -206,7 +375,7 @@ impl Debugger {
                     panic!("Unexpected I/O error: {:?}", err);
                 }
                 Ok(line) => {
-                    if line.trim().len() == 0 {
+                    if line.trim().is_empty() {
                         continue;
                     }
                     self.readline.add_history_entry(line.as_str());
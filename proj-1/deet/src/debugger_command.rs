@@ -1,29 +1,197 @@
 pub enum DebuggerCommand {
     Quit,
     Run(Vec<String>),
+    Attach(i32),
+    Detach,
     Continue,
     BackTrace,
-    Breakpoint(String),
+    Step,
+    Next,
+    Finish,
+    Breakpoint {
+        location: String,
+        condition: Option<String>,
+    },
+    InfoBreakpoints,
+    InfoLocals,
+    InfoThreads,
+    Thread(usize),
+    Print(String),
+    List(Option<String>),
+    Delete(usize),
+    Ignore(usize, usize),
+    Enable(usize),
+    Disable(usize),
 }
 
 impl DebuggerCommand {
-    pub fn from_tokens(tokens: &Vec<&str>) -> Option<DebuggerCommand> {
-        match tokens[0] {
+    pub fn from_tokens(tokens: &[&str]) -> Option<DebuggerCommand> {
+        match *tokens.first()? {
             "q" | "quit" => Some(DebuggerCommand::Quit),
             "r" | "run" => {
                 let args = tokens[1..].to_vec();
                 Some(DebuggerCommand::Run(
                     args.iter().map(|s| s.to_string()).collect(),
                 ))
+            }
+            "attach" => match tokens.get(1)?.parse() {
+                Ok(pid) if pid > 0 => Some(DebuggerCommand::Attach(pid)),
+                _ => None,
             },
+            "detach" => Some(DebuggerCommand::Detach),
             "c" | "cont" | "continue" => Some(DebuggerCommand::Continue),
             "bt" | "back" | "backtrace" => Some(DebuggerCommand::BackTrace),
-            "b" | "bp" | "breakpoint" => {
-                let addr = String::from(tokens[1]);
-                Some(DebuggerCommand::Breakpoint(addr))
+            "s" | "step" => Some(DebuggerCommand::Step),
+            "n" | "next" => Some(DebuggerCommand::Next),
+            "fin" | "finish" => Some(DebuggerCommand::Finish),
+            "b" | "bp" | "break" | "breakpoint" => {
+                let location = String::from(*tokens.get(1)?);
+                let condition = match tokens.get(2) {
+                    None => None,
+                    Some(&"if") if tokens.len() > 3 => Some(tokens[3..].join(" ")),
+                    Some(_) => return None,
+                };
+                Some(DebuggerCommand::Breakpoint {
+                    location,
+                    condition,
+                })
             }
+            "i" | "info" => match *tokens.get(1)? {
+                "b" | "break" | "breakpoints" => Some(DebuggerCommand::InfoBreakpoints),
+                "locals" => Some(DebuggerCommand::InfoLocals),
+                "threads" => Some(DebuggerCommand::InfoThreads),
+                _ => None,
+            },
+            "t" | "thread" => Some(DebuggerCommand::Thread(tokens.get(1)?.parse().ok()?)),
+            "p" | "print" => Some(DebuggerCommand::Print(tokens.get(1)?.to_string())),
+            "l" | "list" => match tokens.len() {
+                1 => Some(DebuggerCommand::List(None)),
+                2 => Some(DebuggerCommand::List(Some(tokens[1].to_string()))),
+                _ => None,
+            },
+            "d" | "delete" => Some(DebuggerCommand::Delete(tokens.get(1)?.parse().ok()?)),
+            "ignore" => Some(DebuggerCommand::Ignore(
+                tokens.get(1)?.parse().ok()?,
+                tokens.get(2)?.parse().ok()?,
+            )),
+            "enable" => Some(DebuggerCommand::Enable(tokens.get(1)?.parse().ok()?)),
+            "disable" => Some(DebuggerCommand::Disable(tokens.get(1)?.parse().ok()?)),
             // Default case:
             _ => None,
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_from_tokens() {
+        assert!(matches!(
+            DebuggerCommand::from_tokens(&["q"]),
+            Some(DebuggerCommand::Quit)
+        ));
+        match DebuggerCommand::from_tokens(&["run", "a", "b"]) {
+            Some(DebuggerCommand::Run(args)) => assert_eq!(args, vec!["a", "b"]),
+            _ => panic!("expected a run command"),
+        }
+        assert!(matches!(
+            DebuggerCommand::from_tokens(&["attach", "1234"]),
+            Some(DebuggerCommand::Attach(1234))
+        ));
+        assert!(matches!(
+            DebuggerCommand::from_tokens(&["detach"]),
+            Some(DebuggerCommand::Detach)
+        ));
+        assert!(matches!(
+            DebuggerCommand::from_tokens(&["n"]),
+            Some(DebuggerCommand::Next)
+        ));
+        assert!(matches!(
+            DebuggerCommand::from_tokens(&["step"]),
+            Some(DebuggerCommand::Step)
+        ));
+        assert!(matches!(
+            DebuggerCommand::from_tokens(&["fin"]),
+            Some(DebuggerCommand::Finish)
+        ));
+        match DebuggerCommand::from_tokens(&["break", "main"]) {
+            Some(DebuggerCommand::Breakpoint {
+                location,
+                condition: None,
+            }) => assert_eq!(location, "main"),
+            _ => panic!("expected a breakpoint command"),
+        }
+        match DebuggerCommand::from_tokens(&["b", "func2", "if", "a", ">", "5"]) {
+            Some(DebuggerCommand::Breakpoint {
+                location,
+                condition: Some(condition),
+            }) => {
+                assert_eq!(location, "func2");
+                assert_eq!(condition, "a > 5");
+            }
+            _ => panic!("expected a conditional breakpoint command"),
+        }
+        assert!(matches!(
+            DebuggerCommand::from_tokens(&["ignore", "1", "3"]),
+            Some(DebuggerCommand::Ignore(1, 3))
+        ));
+        assert!(matches!(
+            DebuggerCommand::from_tokens(&["info", "breakpoints"]),
+            Some(DebuggerCommand::InfoBreakpoints)
+        ));
+        assert!(matches!(
+            DebuggerCommand::from_tokens(&["i", "locals"]),
+            Some(DebuggerCommand::InfoLocals)
+        ));
+        assert!(matches!(
+            DebuggerCommand::from_tokens(&["info", "threads"]),
+            Some(DebuggerCommand::InfoThreads)
+        ));
+        assert!(matches!(
+            DebuggerCommand::from_tokens(&["thread", "2"]),
+            Some(DebuggerCommand::Thread(2))
+        ));
+        match DebuggerCommand::from_tokens(&["p", "counter"]) {
+            Some(DebuggerCommand::Print(name)) => assert_eq!(name, "counter"),
+            _ => panic!("expected a print command"),
+        }
+        assert!(matches!(
+            DebuggerCommand::from_tokens(&["l"]),
+            Some(DebuggerCommand::List(None))
+        ));
+        match DebuggerCommand::from_tokens(&["list", "loop.c:4"]) {
+            Some(DebuggerCommand::List(Some(location))) => assert_eq!(location, "loop.c:4"),
+            _ => panic!("expected a list command"),
+        }
+        assert!(matches!(
+            DebuggerCommand::from_tokens(&["d", "2"]),
+            Some(DebuggerCommand::Delete(2))
+        ));
+        assert!(matches!(
+            DebuggerCommand::from_tokens(&["disable", "0"]),
+            Some(DebuggerCommand::Disable(0))
+        ));
+    }
+
+    #[test]
+    fn test_from_tokens_invalid() {
+        assert!(DebuggerCommand::from_tokens(&[]).is_none());
+        assert!(DebuggerCommand::from_tokens(&["b"]).is_none());
+        assert!(DebuggerCommand::from_tokens(&["b", "main", "if"]).is_none());
+        assert!(DebuggerCommand::from_tokens(&["b", "main", "x", ">", "1"]).is_none());
+        assert!(DebuggerCommand::from_tokens(&["ignore", "1"]).is_none());
+        assert!(DebuggerCommand::from_tokens(&["attach"]).is_none());
+        assert!(DebuggerCommand::from_tokens(&["attach", "0"]).is_none());
+        assert!(DebuggerCommand::from_tokens(&["attach", "-5"]).is_none());
+        assert!(DebuggerCommand::from_tokens(&["frobnicate"]).is_none());
+        assert!(DebuggerCommand::from_tokens(&["info"]).is_none());
+        assert!(DebuggerCommand::from_tokens(&["print"]).is_none());
+        assert!(DebuggerCommand::from_tokens(&["thread"]).is_none());
+        assert!(DebuggerCommand::from_tokens(&["list", "main", "10"]).is_none());
+        assert!(DebuggerCommand::from_tokens(&["thread", "main"]).is_none());
+        assert!(DebuggerCommand::from_tokens(&["delete", "one"]).is_none());
+        assert!(DebuggerCommand::from_tokens(&["enable", "-1"]).is_none());
+    }
+}
@@ -0,0 +1,4 @@
+//! The parts of deet's REPL that don't need a terminal, so they can be tested and fuzzed on
+//! their own.
+
+pub mod debugger_command;
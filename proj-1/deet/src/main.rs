@@ -1,24 +1,37 @@
 mod debugger;
-mod debugger_command;
-mod inferior;
-mod dwarf_data;
-mod gimli_wrapper;
 
 use crate::debugger::Debugger;
+use errors::Error;
 use nix::sys::signal::{signal, SigHandler, Signal};
 use std::env;
 
 fn main() {
     let args: Vec<String> = env::args().collect();
-    if args.len() != 2 {
-        println!("Usage: {} <target program>", args[0]);
-        std::process::exit(1);
-    }
-    let target = &args[1];
+    let usage = || {
+        Error::Usage(format!(
+            "Usage: {} <target program> | attach <pid>",
+            args[0]
+        ))
+    };
+    let pid = match args.len() {
+        2 => None,
+        3 if args[1] == "attach" => match args[2].parse() {
+            Ok(pid) if pid > 0 => Some(pid),
+            _ => usage().exit(),
+        },
+        _ => usage().exit(),
+    };
 
     // Disable handling of ctrl+c in this process (so that ctrl+c only gets delivered to child
     // processes)
     unsafe { signal(Signal::SIGINT, SigHandler::SigIgn) }.expect("Error disabling SIGINT handling");
 
-    Debugger::new(target).run();
+    let debugger = match pid {
+        Some(pid) => Debugger::attach(pid),
+        None => Debugger::new(&args[1]),
+    };
+    match debugger {
+        Ok(mut debugger) => debugger.run(),
+        Err(err) => err.exit(),
+    }
 }
@@ -76,14 +76,14 @@ async fn main() {
     // Initialize the logging library. You can print log messages using the `log` macros:
     // https://docs.rs/log/0.4.8/log/ You are welcome to continue using print! statements; this
     // just looks a little prettier.
-    if let Err(_) = std::env::var("RUST_LOG") {
+    if std::env::var("RUST_LOG").is_err() {
         std::env::set_var("RUST_LOG", "debug");
     }
     pretty_env_logger::init();
 
     // Parse the command line arguments passed to this program
     let options = CmdOptions::parse();
-    if options.upstream.len() < 1 {
+    if options.upstream.is_empty() {
         log::error!("At least one upstream server must be specified using the --upstream option.");
         std::process::exit(1);
     }
@@ -146,8 +146,7 @@ async fn health_check(state: Arc<RwLock<ProxyState>>) {
     loop {
         thread::sleep(time::Duration::from_secs(interval as u64));
 
-        for idx in 0..addresses.len() {
-            let addr = &addresses[idx];
+        for (idx, addr) in addresses.iter().enumerate() {
             let request = http::Request::builder()
             .method(http::Method::GET)
             .uri(&path)
@@ -240,11 +239,10 @@ async fn send_response(client_conn: &mut TcpStream, response: &http::Response<Ve
     log::info!(
         "{} <- {}",
         client_ip,
-        response::format_response_line(&response)
+        response::format_response_line(response)
     );
-    if let Err(error) = response::write_to_stream(&response, client_conn).await {
+    if let Err(error) = response::write_to_stream(response, client_conn).await {
         log::warn!("Failed to send response to client: {}", error);
-        return;
     }
 }
 
@@ -6,7 +6,9 @@ const MAX_HEADERS_SIZE: usize = 8000;
 const MAX_BODY_SIZE: usize = 10000000;
 const MAX_NUM_HEADERS: usize = 32;
 
+// The payloads are only ever shown through Debug.
 #[derive(Debug)]
+#[allow(dead_code, clippy::enum_variant_names)]
 pub enum Error {
     /// Client hung up before sending a complete request. IncompleteRequest contains the number of
     /// bytes that were successfully read before the client hung up
@@ -74,10 +76,11 @@ pub fn extend_header_value(
 /// * If there is data in the buffer that is definitely not a valid HTTP request, returns Err(Error)
 ///
 /// You won't need to touch this function.
+#[allow(clippy::type_complexity)]
 fn parse_request(buffer: &[u8]) -> Result<Option<(http::Request<Vec<u8>>, usize)>, Error> {
     let mut headers = [httparse::EMPTY_HEADER; MAX_NUM_HEADERS];
     let mut req = httparse::Request::new(&mut headers);
-    let res = req.parse(buffer).or_else(|err| Err(Error::MalformedRequest(err)))?;
+    let res = req.parse(buffer).map_err(Error::MalformedRequest)?;
 
     if let httparse::Status::Complete(len) = res {
         let mut request = http::Request::builder()
@@ -110,8 +113,9 @@ async fn read_headers(stream: &mut TcpStream) -> Result<http::Request<Vec<u8>>,
     loop {
         // Read bytes from the connection into the buffer, starting at position bytes_read
         let new_bytes = stream
-            .read(&mut request_buffer[bytes_read..]).await
-            .or_else(|err| Err(Error::ConnectionError(err)))?;
+            .read(&mut request_buffer[bytes_read..])
+            .await
+            .map_err(Error::ConnectionError)?;
         if new_bytes == 0 {
             // We didn't manage to read a complete request
             return Err(Error::IncompleteRequest(bytes_read));
@@ -147,7 +151,10 @@ async fn read_body(
         // Read up to 512 bytes at a time. (If the client only sent a small body, then only allocate
         // space to read that body.)
         let mut buffer = vec![0_u8; min(512, content_length)];
-        let bytes_read = stream.read(&mut buffer).await.or_else(|err| Err(Error::ConnectionError(err)))?;
+        let bytes_read = stream
+            .read(&mut buffer)
+            .await
+            .map_err(Error::ConnectionError)?;
 
         // Make sure the client is still sending us bytes
         if bytes_read == 0 {
@@ -199,20 +206,29 @@ pub async fn write_to_stream(
     request: &http::Request<Vec<u8>>,
     stream: &mut TcpStream,
 ) -> Result<(), std::io::Error> {
-    stream.write(&format_request_line(request).into_bytes()).await?;
-    stream.write(&['\r' as u8, '\n' as u8]).await?; // \r\n
+    stream
+        .write_all(&format_request_line(request).into_bytes())
+        .await?;
+    stream.write_all(b"\r\n").await?; // \r\n
     for (header_name, header_value) in request.headers() {
-        stream.write(&format!("{}: ", header_name).as_bytes()).await?;
-        stream.write(header_value.as_bytes()).await?;
-        stream.write(&['\r' as u8, '\n' as u8]).await?; // \r\n
+        stream
+            .write_all(format!("{}: ", header_name).as_bytes())
+            .await?;
+        stream.write_all(header_value.as_bytes()).await?;
+        stream.write_all(b"\r\n").await?; // \r\n
     }
-    stream.write(&['\r' as u8, '\n' as u8]).await?;
-    if request.body().len() > 0 {
-        stream.write(request.body()).await?;
+    stream.write_all(b"\r\n").await?;
+    if !request.body().is_empty() {
+        stream.write_all(request.body()).await?;
     }
     Ok(())
 }
 
 pub fn format_request_line(request: &http::Request<Vec<u8>>) -> String {
-    format!("{} {} {:?}", request.method(), request.uri(), request.version())
+    format!(
+        "{} {} {:?}",
+        request.method(),
+        request.uri(),
+        request.version()
+    )
 }
@@ -5,7 +5,9 @@ const MAX_HEADERS_SIZE: usize = 8000;
 const MAX_BODY_SIZE: usize = 10000000;
 const MAX_NUM_HEADERS: usize = 32;
 
+// The payloads are only ever shown through Debug.
 #[derive(Debug)]
+#[allow(dead_code, clippy::enum_variant_names)]
 pub enum Error {
     /// Client hung up before sending a complete request
     IncompleteResponse,
@@ -52,12 +54,11 @@ fn get_content_length(response: &http::Response<Vec<u8>>) -> Result<Option<usize
 ///   Err(Error)
 ///
 /// You won't need to touch this function.
+#[allow(clippy::type_complexity)]
 fn parse_response(buffer: &[u8]) -> Result<Option<(http::Response<Vec<u8>>, usize)>, Error> {
     let mut headers = [httparse::EMPTY_HEADER; MAX_NUM_HEADERS];
     let mut resp = httparse::Response::new(&mut headers);
-    let res = resp
-        .parse(buffer)
-        .or_else(|err| Err(Error::MalformedResponse(err)))?;
+    let res = resp.parse(buffer).map_err(Error::MalformedResponse)?;
 
     if let httparse::Status::Complete(len) = res {
         let mut response = http::Response::builder()
@@ -89,8 +90,9 @@ async fn read_headers(stream: &mut TcpStream) -> Result<http::Response<Vec<u8>>,
     loop {
         // Read bytes from the connection into the buffer, starting at position bytes_read
         let new_bytes = stream
-            .read(&mut response_buffer[bytes_read..]).await
-            .or_else(|err| Err(Error::ConnectionError(err)))?;
+            .read(&mut response_buffer[bytes_read..])
+            .await
+            .map_err(Error::ConnectionError)?;
         if new_bytes == 0 {
             // We didn't manage to read a complete response
             return Err(Error::IncompleteResponse);
@@ -114,7 +116,10 @@ async fn read_headers(stream: &mut TcpStream) -> Result<http::Response<Vec<u8>>,
 /// present, it reads that many bytes; otherwise, it reads bytes until the connection is closed.
 ///
 /// You will need to modify this function in Milestone 2.
-async fn read_body(stream: &mut TcpStream, response: &mut http::Response<Vec<u8>>) -> Result<(), Error> {
+async fn read_body(
+    stream: &mut TcpStream,
+    response: &mut http::Response<Vec<u8>>,
+) -> Result<(), Error> {
     // The response may or may not supply a Content-Length header. If it provides the header, then
     // we want to read that number of bytes; if it does not, we want to keep reading bytes until
     // the connection is closed.
@@ -123,8 +128,9 @@ async fn read_body(stream: &mut TcpStream, response: &mut http::Response<Vec<u8>
     while content_length.is_none() || response.body().len() < content_length.unwrap() {
         let mut buffer = [0_u8; 512];
         let bytes_read = stream
-            .read(&mut buffer).await
-            .or_else(|err| Err(Error::ConnectionError(err)))?;
+            .read(&mut buffer)
+            .await
+            .map_err(Error::ConnectionError)?;
         if bytes_read == 0 {
             // The server has hung up!
             if content_length.is_none() {
@@ -182,16 +188,20 @@ pub async fn write_to_stream(
     response: &http::Response<Vec<u8>>,
     stream: &mut TcpStream,
 ) -> Result<(), std::io::Error> {
-    stream.write(&format_response_line(response).into_bytes()).await?;
-    stream.write(&['\r' as u8, '\n' as u8]).await?; // \r\n
+    stream
+        .write_all(&format_response_line(response).into_bytes())
+        .await?;
+    stream.write_all(b"\r\n").await?; // \r\n
     for (header_name, header_value) in response.headers() {
-        stream.write(&format!("{}: ", header_name).as_bytes()).await?;
-        stream.write(header_value.as_bytes()).await?;
-        stream.write(&['\r' as u8, '\n' as u8]).await?; // \r\n
+        stream
+            .write_all(format!("{}: ", header_name).as_bytes())
+            .await?;
+        stream.write_all(header_value.as_bytes()).await?;
+        stream.write_all(b"\r\n").await?; // \r\n
     }
-    stream.write(&['\r' as u8, '\n' as u8]).await?;
-    if response.body().len() > 0 {
-        stream.write(response.body()).await?;
+    stream.write_all(b"\r\n").await?;
+    if !response.body().is_empty() {
+        stream.write_all(response.body()).await?;
     }
     Ok(())
 }
@@ -42,10 +42,12 @@ impl BalanceBeam {
         cmd.kill_on_drop(true);
         cmd.stdout(std::process::Stdio::piped());
         cmd.stderr(std::process::Stdio::piped());
-        let mut child = cmd.spawn().expect(&format!(
-            "Could not execute balancebeam binary {}",
-            BalanceBeam::target_bin_path().to_str().unwrap()
-        ));
+        let mut child = cmd.spawn().unwrap_or_else(|_| {
+            panic!(
+                "Could not execute balancebeam binary {}",
+                BalanceBeam::target_bin_path().to_str().unwrap()
+            )
+        });
 
         // Print output from the child. We want to intercept and log this output (instead of letting
         // the child inherit stderr and print directly to the terminal) so that the output can be
@@ -1,3 +1,6 @@
+// Each test binary compiles its own copy of these helpers and only uses some.
+#![allow(dead_code, unused_imports)]
+
 mod balancebeam;
 mod echo_server;
 mod error_server;
@@ -6,20 +6,23 @@ fn main() {
     println!("Hi! Try running \"cargo test\" to run tests.");
 }
 
+#[allow(dead_code)]
 fn add_n(v: Vec<i32>, n: i32) -> Vec<i32> {
     let mut vec = Vec::new();
     for ele in v {
         vec.push(ele + n);
     }
-    return vec;
+    vec
 }
 
+#[allow(dead_code)]
 fn add_n_inplace(v: &mut Vec<i32>, n: i32) {
     for ele in v {
         *ele += n;
     }
 }
 
+#[allow(dead_code)]
 fn dedup(v: &mut Vec<i32>) {
     let mut set: HashSet<i32> = HashSet::new();
     let mut len = v.len();
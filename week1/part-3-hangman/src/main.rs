@@ -42,14 +42,14 @@ fn main() {
     let mut guesses = String::from("");
     while incorrect_cnt < NUM_INCORRECT_GUESSES && set.len() < secret_word_chars.len() {
         print!("The word so far is: ");
-        for i in 0..secret_word_chars.len() {
+        for (i, ch) in secret_word_chars.iter().enumerate() {
             if set.contains(&i) {
-                print!("{}", secret_word_chars[i]);
+                print!("{}", ch);
             } else {
                 print!("-");
             }
         }
-        print!("\n");
+        println!();
         println!("You have guessed: {}", guesses);
 
         print!("Please enter a letter: ");
@@ -73,7 +73,7 @@ fn main() {
             println!("Wrong guess");
             incorrect_cnt += 1;
         }
-        println!("");
+        println!();
     }
     if incorrect_cnt == NUM_INCORRECT_GUESSES {
         println!("Sorry, you run out of guesses");
@@ -20,7 +20,7 @@ fn read_file_lines(filename: &String) -> Result<Vec<String>, io::Error> {
     Ok(vec)
 }
 
-fn lcs(seq1: &Vec<String>, seq2: &Vec<String>) -> Grid {
+fn lcs(seq1: &[String], seq2: &[String]) -> Grid {
     // Note: Feel free to use unwrap() in this code, as long as you're basically certain it'll
     // never happen. Conceptually, unwrap() is justified here, because there's not really any error
     // condition you're watching out for (i.e. as long as your code is written correctly, nothing
@@ -35,9 +35,9 @@ fn lcs(seq1: &Vec<String>, seq2: &Vec<String>) -> Grid {
     for j in 0..n + 1 {
         c.set(0, j, 0).unwrap();
     }
-    for i in 0..m {
-        for j in 0..n {
-            if &seq1[i] == &seq2[j] {
+    for (i, line1) in seq1.iter().enumerate() {
+        for (j, line2) in seq2.iter().enumerate() {
+            if line1 == line2 {
                 let val = c.get(i, j).unwrap() + 1;
                 c.set(i + 1, j + 1, val).unwrap();
             } else {
@@ -60,7 +60,7 @@ fn print_diff(lcs_table: &Grid, lines1: &Vec<String>, lines2: &Vec<String>, i: u
         print_diff(lcs_table, lines1, lines2, i - 1, j);
         println!("< {}", lines1[i - 1]);
     } else {
-        println!("")
+        println!()
     }
 }
 
@@ -73,8 +73,8 @@ fn main() {
     let filename1 = &args[1];
     let filename2 = &args[2];
 
-    let seq1 = read_file_lines(&filename1).unwrap_or_else(|_| panic!("fail to read from {}", &filename1));
-    let seq2 = read_file_lines(&filename2).unwrap_or_else(|_| panic!("fail to read from {}", &filename2));
+    let seq1 = read_file_lines(filename1).unwrap_or_else(|_| panic!("fail to read from {}", &filename1));
+    let seq2 = read_file_lines(filename2).unwrap_or_else(|_| panic!("fail to read from {}", &filename2));
 
     let g = lcs(&seq1, &seq2);
     print_diff(&g, &seq1, &seq2, seq1.len(), seq2.len());
@@ -115,8 +115,8 @@ mod test {
         println!("Expected:");
         expected.display();
         let result = lcs(
-            &"abcd".chars().map(|c| c.to_string()).collect(),
-            &"adb".chars().map(|c| c.to_string()).collect(),
+            &"abcd".chars().map(|c| c.to_string()).collect::<Vec<_>>(),
+            &"adb".chars().map(|c| c.to_string()).collect::<Vec<_>>(),
         );
         println!("Got:");
         result.display();
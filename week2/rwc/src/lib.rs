@@ -0,0 +1,499 @@
+//! rwc's counting and argument handling, separate from the command line tool so they can be
+//! tested and fuzzed on their own.
+
+use common::Counts;
+use errors::Error;
+use parallel_map::ThreadPool;
+use std::fs::{self, File};
+use std::io::{self, BufRead, Read};
+
+/// About how much of the input each worker counts at a time when counting in parallel.
+const CHUNK_SIZE: usize = 1 << 20;
+
+/// Which counts to print. Whichever are chosen, they are printed in wc's order: lines, words,
+/// characters, bytes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Columns {
+    pub lines: bool,
+    pub words: bool,
+    pub chars: bool,
+    pub bytes: bool,
+}
+
+impl Columns {
+    /// The counts printed when no flag picks any: lines, words and bytes, like wc.
+    pub fn wc_default() -> Columns {
+        Columns {
+            lines: true,
+            words: true,
+            chars: false,
+            bytes: true,
+        }
+    }
+
+    fn none() -> Columns {
+        Columns {
+            lines: false,
+            words: false,
+            chars: false,
+            bytes: false,
+        }
+    }
+
+    /// The chosen counts out of `counts`, in the order they are printed.
+    pub fn select(&self, counts: &Counts) -> Vec<usize> {
+        [
+            (self.lines, counts.lines),
+            (self.words, counts.words),
+            (self.chars, counts.chars),
+            (self.bytes, counts.bytes),
+        ]
+        .iter()
+        .filter(|(chosen, _)| *chosen)
+        .map(|(_, count)| *count)
+        .collect()
+    }
+}
+
+/// rwc's command line.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Args {
+    pub columns: Columns,
+    /// How many threads to count with. With just one, the input is counted as it is read.
+    pub threads: usize,
+    /// The files to count, where "-" is standard input. Empty if none were named, in which case
+    /// standard input is counted (and printed without a name).
+    pub inputs: Vec<String>,
+}
+
+impl Args {
+    /// Parses the command line, program name first. Flags can be combined ("-lw") and go
+    /// anywhere before a "--". The number of threads defaults to the number of CPUs.
+    pub fn parse(args: &[String]) -> errors::Result<Args> {
+        let usage = |problem: String| {
+            Error::Usage(format!(
+                "{}\nUsage: {} [-clmw] [file ...]",
+                problem,
+                args.first().map_or("rwc", String::as_str)
+            ))
+        };
+        let mut columns = Columns::none();
+        let mut threads = num_cpus::get();
+        let mut inputs = Vec::new();
+        let mut flags_done = false;
+        let mut args_iter = args.iter().skip(1);
+        while let Some(arg) = args_iter.next() {
+            if flags_done || arg == "-" || !arg.starts_with('-') {
+                inputs.push(arg.clone());
+                continue;
+            }
+            if arg == "--threads" || arg.starts_with("--threads=") {
+                let value = match arg.strip_prefix("--threads=") {
+                    Some(value) => Some(value),
+                    None => args_iter.next().map(String::as_str),
+                };
+                threads = match value.map(str::parse) {
+                    Some(Ok(threads)) if threads > 0 => threads,
+                    _ => return Err(usage("--threads needs a positive number".to_string())),
+                };
+                continue;
+            }
+            match arg.as_str() {
+                "--" => flags_done = true,
+                "--lines" => columns.lines = true,
+                "--words" => columns.words = true,
+                "--chars" => columns.chars = true,
+                "--bytes" => columns.bytes = true,
+                _ if arg.starts_with("--") => {
+                    return Err(usage(format!("unrecognized option {}", arg)))
+                }
+                _ => {
+                    for flag in arg.chars().skip(1) {
+                        match flag {
+                            'l' => columns.lines = true,
+                            'w' => columns.words = true,
+                            'm' => columns.chars = true,
+                            'c' => columns.bytes = true,
+                            _ => return Err(usage(format!("invalid option -- '{}'", flag))),
+                        }
+                    }
+                }
+            }
+        }
+        if columns == Columns::none() {
+            columns = Columns::wc_default();
+        }
+        Ok(Args {
+            columns,
+            threads,
+            inputs,
+        })
+    }
+}
+
+/// Counts the lines, words, characters and bytes read from `reader`. `name` is only used to
+/// describe where a read error happened.
+pub fn count_reader(name: &str, mut reader: impl BufRead) -> errors::Result<Counts> {
+    let mut counts = Counts::default();
+    let mut line = Vec::new();
+    loop {
+        line.clear();
+        let read = reader.read_until(b'\n', &mut line).map_err(|err| {
+            Error::io(
+                format!("fail to read {} at line {}", name, counts.lines + 1),
+                err,
+            )
+        })?;
+        if read == 0 {
+            return Ok(counts);
+        }
+        counts += Counts::from_bytes(&line);
+    }
+}
+
+fn open(filename: &str) -> errors::Result<io::BufReader<File>> {
+    let file =
+        File::open(filename).map_err(|err| Error::io(format!("fail to open {}", filename), err))?;
+    Ok(io::BufReader::new(file))
+}
+
+pub fn count_file(filename: &str) -> errors::Result<Counts> {
+    count_reader(filename, open(filename)?)
+}
+
+/// Reads a reader in chunks of about `chunk_size` bytes, each one carried on to the end of the
+/// line it stops in, so that no line (and so no word or character) is split between chunks.
+struct Chunks<R> {
+    reader: R,
+    chunk_size: usize,
+    done: bool,
+}
+
+impl<R: BufRead> Iterator for Chunks<R> {
+    type Item = io::Result<Vec<u8>>;
+
+    fn next(&mut self) -> Option<io::Result<Vec<u8>>> {
+        if self.done {
+            return None;
+        }
+        let mut chunk = Vec::with_capacity(self.chunk_size);
+        let read = (&mut self.reader)
+            .take(self.chunk_size as u64)
+            .read_to_end(&mut chunk)
+            .and_then(|_| {
+                if chunk.len() < self.chunk_size {
+                    self.done = true;
+                } else if !chunk.ends_with(b"\n") {
+                    self.reader.read_until(b'\n', &mut chunk)?;
+                }
+                Ok(())
+            });
+        match read {
+            Err(err) => {
+                self.done = true;
+                Some(Err(err))
+            }
+            Ok(()) if chunk.is_empty() => None,
+            Ok(()) => Some(Ok(chunk)),
+        }
+    }
+}
+
+/// Like `count_reader`, but `reader` is read in chunks of whole lines which are counted on
+/// `pool`'s workers while the next ones are read. A read error is reported at the first line of
+/// the chunk it happened in.
+pub fn count_reader_parallel(
+    name: &str,
+    reader: impl BufRead,
+    pool: &ThreadPool,
+) -> errors::Result<Counts> {
+    count_chunks(name, reader, pool, CHUNK_SIZE)
+}
+
+fn count_chunks(
+    name: &str,
+    reader: impl BufRead,
+    pool: &ThreadPool,
+    chunk_size: usize,
+) -> errors::Result<Counts> {
+    let chunks = Chunks {
+        reader,
+        chunk_size,
+        done: false,
+    };
+    let mut counts = Counts::default();
+    for chunk_counts in pool.map_iter(chunks, |chunk| {
+        chunk.map(|chunk| Counts::from_bytes(&chunk))
+    }) {
+        counts += chunk_counts.map_err(|err| {
+            Error::io(
+                format!("fail to read {} at line {}", name, counts.lines + 1),
+                err,
+            )
+        })?;
+    }
+    Ok(counts)
+}
+
+/// Counts the file `name`, or standard input if it is "-". Given a pool, the counting is spread
+/// over its workers.
+pub fn count_input(name: &str, pool: Option<&ThreadPool>) -> errors::Result<Counts> {
+    let (name, reader): (&str, Box<dyn BufRead>) = if name == "-" {
+        ("standard input", Box::new(io::stdin().lock()))
+    } else {
+        (name, Box::new(open(name)?))
+    };
+    match pool {
+        Some(pool) => count_reader_parallel(name, reader, pool),
+        None => count_reader(name, reader),
+    }
+}
+
+/// Whether standard input is redirected from a regular file, whose size is known before it is
+/// read, rather than being a pipe or a terminal.
+pub fn stdin_is_file() -> bool {
+    fs::metadata("/dev/stdin").is_ok_and(|metadata| metadata.is_file())
+}
+
+/// The width every count is padded to, following GNU wc: wide enough for the total number of
+/// bytes, and at least 7 when an input's size isn't known up front (standard input that isn't a
+/// file). A single count of a single input isn't padded at all.
+pub fn column_width(
+    columns: Columns,
+    num_inputs: usize,
+    unsized_input: bool,
+    bytes: usize,
+) -> usize {
+    if num_inputs == 1 && columns.select(&Counts::default()).len() == 1 {
+        return 1;
+    }
+    let digits = bytes.to_string().len();
+    if unsized_input {
+        digits.max(7)
+    } else {
+        digits
+    }
+}
+
+/// One line of output: the chosen counts, each right-aligned to `width`, then the input's name
+/// if it has one.
+pub fn format_row(counts: &Counts, columns: Columns, width: usize, name: Option<&str>) -> String {
+    let mut fields: Vec<String> = columns
+        .select(counts)
+        .iter()
+        .map(|count| format!("{:>width$}", count, width = width))
+        .collect();
+    fields.extend(name.map(str::to_string));
+    fields.join(" ")
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use common::test_utils::TempFile;
+    use std::io::Read;
+
+    fn args(args: &[&str]) -> errors::Result<Args> {
+        let args: Vec<String> = args.iter().map(|arg| arg.to_string()).collect();
+        Args::parse(&args)
+    }
+
+    #[test]
+    fn test_count_file() {
+        let file = TempFile::with_contents("one two\nthree\n\nfour  five six\n");
+        assert_eq!(
+            count_file(file.path().to_str().unwrap()).unwrap(),
+            Counts {
+                lines: 4,
+                words: 6,
+                chars: 30,
+                bytes: 30,
+            }
+        );
+    }
+
+    #[test]
+    fn test_count_reader() {
+        assert_eq!(
+            count_reader("input", &b"a b\r\nc"[..]).unwrap(),
+            Counts {
+                lines: 1,
+                words: 3,
+                chars: 6,
+                bytes: 6,
+            }
+        );
+        assert_eq!(count_reader("input", &b""[..]).unwrap(), Counts::default());
+    }
+
+    #[test]
+    fn test_count_missing_file() {
+        let err = count_file("this/file/does/not/exist").unwrap_err();
+        assert!(
+            matches!(err, Error::Io { ref source, .. } if source.kind() == io::ErrorKind::NotFound)
+        );
+        assert_eq!(err.exit_code(), 74);
+    }
+
+    #[test]
+    fn test_count_non_utf8_file() {
+        // Counted like wc does: the invalid bytes are bytes, but not characters
+        let file = TempFile::with_contents(b"fine\n\xff\xfe\n");
+        assert_eq!(
+            count_file(file.path().to_str().unwrap()).unwrap(),
+            Counts {
+                lines: 2,
+                words: 1,
+                chars: 6,
+                bytes: 8,
+            }
+        );
+    }
+
+    #[test]
+    fn test_read_error() {
+        struct Failing;
+
+        impl Read for Failing {
+            fn read(&mut self, _buf: &mut [u8]) -> io::Result<usize> {
+                Err(io::Error::other("disk on fire"))
+            }
+        }
+
+        let reader = io::BufReader::new((&b"one\ntwo\n"[..]).chain(Failing));
+        let err = count_reader("input", reader).unwrap_err();
+        assert!(
+            err.to_string().contains("at line 3"),
+            "unexpected message: {}",
+            err
+        );
+
+        let reader = io::BufReader::new((&b"one\ntwo\n"[..]).chain(Failing));
+        let err = count_chunks("input", reader, &ThreadPool::new(2), 4).unwrap_err();
+        assert!(
+            err.to_string().contains("at line 3"),
+            "unexpected message: {}",
+            err
+        );
+    }
+
+    #[test]
+    fn test_parallel_matches_sequential() {
+        let inputs: [&[u8]; 6] = [
+            b"",
+            b"one two\nthree\n\nfour  five six\n",
+            b"no newline at the end",
+            "h\u{e9}llo w\u{f6}rld\u{a0}x\n\u{1f980}\u{1f980} \u{1f980}\n".as_bytes(),
+            b"\xff\xfe \x07a\x07b\n\n\n   \t\r\n",
+            b"a line much longer than any of the chunks it gets read in\nshort\n",
+        ];
+        let pool = ThreadPool::new(3);
+        for input in &inputs {
+            let expected = count_reader("input", *input).unwrap();
+            for chunk_size in 1..10 {
+                assert_eq!(
+                    count_chunks("input", *input, &pool, chunk_size).unwrap(),
+                    expected,
+                    "{:?} in chunks of {}",
+                    String::from_utf8_lossy(input),
+                    chunk_size
+                );
+            }
+            assert_eq!(
+                count_reader_parallel("input", *input, &pool).unwrap(),
+                expected
+            );
+        }
+    }
+
+    #[test]
+    fn test_parallel_large_file() {
+        let line = "the quick brown fox jumps over the lazy d\u{f6}g\n";
+        let file = TempFile::with_contents(line.repeat(3 * CHUNK_SIZE / line.len()));
+        let name = file.path().to_str().unwrap();
+        let pool = ThreadPool::new(4);
+        assert_eq!(
+            count_input(name, Some(&pool)).unwrap(),
+            count_input(name, None).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_parse_args() {
+        assert_eq!(
+            args(&["rwc"]).unwrap(),
+            Args {
+                columns: Columns::wc_default(),
+                threads: num_cpus::get(),
+                inputs: vec![],
+            }
+        );
+        assert_eq!(
+            args(&["rwc", "a.txt", "-lm", "-", "--threads", "3", "--", "-w"]).unwrap(),
+            Args {
+                columns: Columns {
+                    lines: true,
+                    words: false,
+                    chars: true,
+                    bytes: false,
+                },
+                threads: 3,
+                inputs: vec!["a.txt".to_string(), "-".to_string(), "-w".to_string()],
+            }
+        );
+        assert_eq!(
+            args(&["rwc", "--bytes", "--words"]).unwrap().columns,
+            Columns {
+                lines: false,
+                words: true,
+                chars: false,
+                bytes: true,
+            }
+        );
+
+        let err = args(&["rwc", "-lx"]).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "invalid option -- 'x'\nUsage: rwc [-clmw] [file ...]"
+        );
+        assert_eq!(err.exit_code(), 64);
+        assert!(args(&["rwc", "--lines=3"]).is_err());
+        assert_eq!(args(&["rwc", "--threads=1"]).unwrap().threads, 1);
+        assert!(args(&["rwc", "--threads", "0"]).is_err());
+        assert!(args(&["rwc", "--threads"]).is_err());
+    }
+
+    #[test]
+    fn test_column_width() {
+        let all = Columns::wc_default();
+        let lines = Columns {
+            lines: true,
+            ..Columns::none()
+        };
+        assert_eq!(column_width(all, 1, false, 12345), 5);
+        assert_eq!(column_width(all, 1, true, 12345), 7);
+        assert_eq!(column_width(all, 2, false, 0), 1);
+        assert_eq!(column_width(lines, 1, true, 12345), 1);
+        assert_eq!(column_width(lines, 2, false, 12345), 5);
+    }
+
+    #[test]
+    fn test_format_row() {
+        let counts = Counts {
+            lines: 2,
+            words: 4,
+            chars: 19,
+            bytes: 23,
+        };
+        assert_eq!(
+            format_row(&counts, Columns::wc_default(), 3, Some("t.txt")),
+            "  2   4  23 t.txt"
+        );
+        let chars_bytes = Columns {
+            chars: true,
+            bytes: true,
+            ..Columns::none()
+        };
+        assert_eq!(format_row(&counts, chars_bytes, 1, None), "19 23");
+    }
+}
@@ -1,32 +1,49 @@
-use std::env;
-use std::process;
-use std::fs::File; // For read_file_lines()
-use std::io::{self, BufRead}; // For read_file_lines()
+use common::Counts;
+use parallel_map::ThreadPool;
+use rwc::{column_width, count_input, format_row, stdin_is_file, Args};
+use std::{env, process};
 
 fn main() {
     let args: Vec<String> = env::args().collect();
-    if args.len() < 2 {
-        println!("Too few arguments.");
-        process::exit(1);
-    }
-    let filename = &args[1];
-    
-    let file = File::open(filename).unwrap_or_else(|_| panic!("fail to open {}", filename));
+    let args = Args::parse(&args).unwrap_or_else(|err| err.exit());
+    // With no files named, standard input is counted and printed without a name
+    let inputs: Vec<Option<&str>> = if args.inputs.is_empty() {
+        vec![None]
+    } else {
+        args.inputs.iter().map(|name| Some(name.as_str())).collect()
+    };
 
-    let mut line_cnt = 0;
-    let mut word_cnt = 0;
-    let mut char_cnt = 0;
+    let pool = if args.threads > 1 {
+        Some(ThreadPool::new(args.threads))
+    } else {
+        None
+    };
 
-    for line in io::BufReader::new(file).lines() {
-        match line {
-            Ok(str) => {
-                line_cnt += 1;
-                char_cnt += str.len();
-                word_cnt += str.split(' ').collect::<Vec<&str>>().len();
-            },
-            Err(err) => panic!("fail to read at line {}", line_cnt)
-        };
+    // Everything is counted before anything is printed, since the column width depends on the
+    // total. An input that can't be counted is reported and skipped, like wc does.
+    let mut rows = Vec::new();
+    let mut exit_code = 0;
+    for &name in &inputs {
+        match count_input(name.unwrap_or("-"), pool.as_ref()) {
+            Ok(counts) => rows.push((counts, name)),
+            Err(err) => {
+                eprintln!("{}", err);
+                exit_code = err.exit_code();
+            }
+        }
     }
 
-    println!("line: {} word: {} char: {}", line_cnt, word_cnt, char_cnt);
+    let total: Counts = rows.iter().map(|(counts, _)| *counts).sum();
+    let reads_stdin = inputs
+        .iter()
+        .any(|name| name.is_none_or(|name| name == "-"));
+    let unsized_input = reads_stdin && !stdin_is_file();
+    let width = column_width(args.columns, inputs.len(), unsized_input, total.bytes);
+    for (counts, name) in &rows {
+        println!("{}", format_row(counts, args.columns, width, *name));
+    }
+    if inputs.len() > 1 {
+        println!("{}", format_row(&total, args.columns, width, Some("total")));
+    }
+    process::exit(exit_code);
 }
@@ -11,7 +11,7 @@ fn main() {
         std::process::exit(1);
     }
     let target = &args[1];
-    let process = ps_utils::get_target(&target).expect("Error in calling ps or pgrep");
+    let process = ps_utils::get_target(target).expect("Error in calling ps or pgrep");
     match &process {
         Some(proc) => proc.print(),
         None => {
@@ -32,37 +32,49 @@ fn main() {
 
 #[cfg(test)]
 mod test {
+    use std::path::PathBuf;
     use std::process::{Child, Command};
 
     fn start_c_program(program: &str) -> Child {
         Command::new(program)
             .spawn()
-            .expect(&format!("Could not find {}. Have you run make?", program))
+            .unwrap_or_else(|_| panic!("Could not find {}. Have you run make?", program))
+    }
+
+    // The test executable lives in target/<profile>/deps, next to which cargo puts the binary
+    // (wherever the workspace's target directory is).
+    fn inspect_fds_bin() -> PathBuf {
+        let mut path = std::env::current_exe().expect("Could not get current test executable path");
+        path.pop();
+        path.pop();
+        path.push("inspect-fds");
+        path
     }
 
     #[test]
     fn test_exit_status_valid_target() {
         let mut subprocess = start_c_program("./multi_pipe_test");
         assert_eq!(
-            Command::new("./target/debug/inspect-fds")
-                .args(&[&subprocess.id().to_string()])
+            Command::new(inspect_fds_bin())
+                .args([&subprocess.id().to_string()])
                 .status()
-                .expect("Could not find target/debug/inspect-fds. Is the binary compiled?")
+                .expect("Could not find the inspect-fds binary. Is it compiled?")
                 .code()
                 .expect("Program was unexpectedly terminated by a signal"),
             0,
             "We expected the program to exit normally, but it didn't."
         );
         let _ = subprocess.kill();
+        let _ = subprocess.wait();
     }
 
     #[test]
     fn test_exit_status_invalid_target() {
         assert_eq!(
-            Command::new("./target/debug/inspect-fds")
-                .args(&["./nonexistent"])
+            Command::new(inspect_fds_bin())
+                .args(["./nonexistent"])
                 .status()
-                .expect("Could not find target/debug/inspect-fds. Is the binary compiled?")
+                .expect("Could not find the inspect-fds binary. Is it compiled?")
                 .code()
                 .expect("Program was unexpectedly terminated by a signal"),
             1,
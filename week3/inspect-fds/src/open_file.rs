@@ -3,8 +3,8 @@ use std::collections::hash_map::DefaultHasher;
 use std::hash::{Hash, Hasher};
 use std::{fmt, fs};
 
-const O_WRONLY: usize = 00000001;
-const O_RDWR: usize = 00000002;
+const O_WRONLY: usize = 0o1;
+const O_RDWR: usize = 0o2;
 const COLORS: [&str; 6] = [
     "\x1B[38;5;9m",
     "\x1B[38;5;10m",
@@ -29,9 +29,9 @@ impl fmt::Display for AccessMode {
         // Match operators are very commonly used with enums in Rust. They function similar to
         // switch statements in other languages (but can be more expressive).
         match self {
-            AccessMode::Read => write!(f, "{}", "read"),
-            AccessMode::Write => write!(f, "{}", "write"),
-            AccessMode::ReadWrite => write!(f, "{}", "read/write"),
+            AccessMode::Read => write!(f, "read"),
+            AccessMode::Write => write!(f, "write"),
+            AccessMode::ReadWrite => write!(f, "read/write"),
         }
     }
 }
@@ -82,13 +82,11 @@ impl OpenFile {
         // our code, but if this were code for a critical system that needs to not crash, then
         // we would want to return an Error instead.
         let re = Regex::new(r"pos:\s*(\d+)").unwrap();
-        Some(
-            re.captures(fdinfo)?
+        re.captures(fdinfo)?
                 .get(1)?
                 .as_str()
                 .parse::<usize>()
-                .ok()?,
-        )
+                .ok()
     }
 
     /// This file takes the contents of /proc/{pid}/fdinfo/{fdnum} for some file descriptor and
@@ -125,11 +123,11 @@ impl OpenFile {
     /// simple way to indicate that "hey, we weren't able to get the necessary information"
     /// without making a big deal of it.)
     pub fn from_fd(pid: usize, fd: usize) -> Option<OpenFile> {
-        let sym_path = String::from(format!("/proc/{}/fd/{}", pid, fd));
+        let sym_path = format!("/proc/{}/fd/{}", pid, fd);
         let file_path = fs::read_link(&sym_path).ok()?.to_string_lossy().into_owned();
         let name = OpenFile::path_to_name(&file_path);
 
-        let info_path = String::from(format!("/proc/{}/fdinfo/{}", pid,fd));
+        let info_path = format!("/proc/{}/fdinfo/{}", pid,fd);
         let info = fs::read_to_string(&info_path).ok()?;
         let cursor = OpenFile::parse_cursor(&info)?;
         let access_mode = OpenFile::parse_access_mode(&info)?;
@@ -149,7 +147,7 @@ impl OpenFile {
             let color = COLORS[(hash_val % COLORS.len() as u64) as usize];
             format!("{}{}{}", color, self.name, CLEAR_COLOR)
         } else {
-            format!("{}", self.name)
+            self.name.to_string()
         }
     }
 }
@@ -163,7 +161,7 @@ mod test {
     fn start_c_program(program: &str) -> Child {
         Command::new(program)
             .spawn()
-            .expect(&format!("Could not find {}. Have you run make?", program))
+            .unwrap_or_else(|_| panic!("Could not find {}. Have you run make?", program))
     }
 
     #[test]
@@ -177,6 +175,7 @@ mod test {
         assert_eq!(open_file.cursor, 0);
         assert_eq!(open_file.access_mode, AccessMode::ReadWrite);
         let _ = test_subprocess.kill();
+        let _ = test_subprocess.wait();
     }
 
     #[test]
@@ -189,5 +188,6 @@ mod test {
             "Expected None because file descriptor 30 is invalid"
         );
         let _ = test_subprocess.kill();
+        let _ = test_subprocess.wait();
     }
 }
@@ -41,7 +41,7 @@ impl Process {
     /// descriptor table.)
     pub fn list_fds(&self) -> Option<Vec<usize>> {
         let mut vec: Vec<usize> = Vec::new();
-        let path = String::from(format!("/proc/{}/fd", self.pid));
+        let path = format!("/proc/{}/fd", self.pid);
         let entries = fs::read_dir(&path).ok()?;
         for entry in entries {
             let fd: usize = entry
@@ -78,7 +78,7 @@ mod test {
     fn start_c_program(program: &str) -> Child {
         Command::new(program)
             .spawn()
-            .expect(&format!("Could not find {}. Have you run make?", program))
+            .unwrap_or_else(|_| panic!("Could not find {}. Have you run make?", program))
     }
 
     #[test]
@@ -92,6 +92,7 @@ mod test {
             vec![0, 1, 2, 4, 5]
         );
         let _ = test_subprocess.kill();
+        let _ = test_subprocess.wait();
     }
 
     #[test]
@@ -103,5 +104,6 @@ mod test {
             "Expected list_fds to return None for a zombie process"
         );
         let _ = test_subprocess.kill();
+        let _ = test_subprocess.wait();
     }
 }
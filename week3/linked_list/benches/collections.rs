@@ -0,0 +1,167 @@
+//! Compares `LinkedList` with `std::collections::LinkedList` and `VecDeque` on
+//! the basic deque operations, iteration, cloning and sorting.
+
+use std::collections::{LinkedList as StdLinkedList, VecDeque};
+
+use criterion::{black_box, criterion_group, criterion_main, BatchSize, Criterion};
+use linked_list::LinkedList;
+
+const LEN: usize = 10_000;
+
+// The operations every compared collection supports, so each workload is
+// written once.
+trait Deque: Clone + Default {
+    fn push_front(&mut self, value: u64);
+    fn push_back(&mut self, value: u64);
+    fn pop_front(&mut self) -> Option<u64>;
+    fn pop_back(&mut self) -> Option<u64>;
+    fn sum(&self) -> u64;
+}
+
+macro_rules! impl_deque {
+    ($($ty:ty),*) => {
+        $(
+            impl Deque for $ty {
+                fn push_front(&mut self, value: u64) {
+                    <$ty>::push_front(self, value)
+                }
+
+                fn push_back(&mut self, value: u64) {
+                    <$ty>::push_back(self, value)
+                }
+
+                fn pop_front(&mut self) -> Option<u64> {
+                    <$ty>::pop_front(self)
+                }
+
+                fn pop_back(&mut self) -> Option<u64> {
+                    <$ty>::pop_back(self)
+                }
+
+                fn sum(&self) -> u64 {
+                    self.iter().sum()
+                }
+            }
+        )*
+    };
+}
+
+impl_deque!(LinkedList<u64>, StdLinkedList<u64>, VecDeque<u64>);
+
+// A fixed pseudo-random sequence (an LCG), so every run sorts the same input.
+fn scrambled() -> impl Iterator<Item = u64> {
+    let mut state = 0x2545_f491_4f6c_dd1du64;
+    (0..LEN).map(move |_| {
+        state = state
+            .wrapping_mul(6_364_136_223_846_793_005)
+            .wrapping_add(1_442_695_040_888_963_407);
+        state >> 33
+    })
+}
+
+fn filled<D: Deque>() -> D {
+    let mut deque = D::default();
+    for value in scrambled() {
+        deque.push_back(value);
+    }
+    deque
+}
+
+fn push_pop_front<D: Deque>() {
+    let mut deque = D::default();
+    for i in 0..LEN as u64 {
+        deque.push_front(i);
+    }
+    while let Some(value) = deque.pop_front() {
+        black_box(value);
+    }
+}
+
+fn push_pop_back<D: Deque>() {
+    let mut deque = D::default();
+    for i in 0..LEN as u64 {
+        deque.push_back(i);
+    }
+    while let Some(value) = deque.pop_back() {
+        black_box(value);
+    }
+}
+
+fn bench_push_pop(c: &mut Criterion) {
+    let mut group = c.benchmark_group("push_pop_front");
+    group.bench_function("linked_list", |b| b.iter(push_pop_front::<LinkedList<u64>>));
+    group.bench_function("std_linked_list", |b| {
+        b.iter(push_pop_front::<StdLinkedList<u64>>)
+    });
+    group.bench_function("vec_deque", |b| b.iter(push_pop_front::<VecDeque<u64>>));
+    group.finish();
+
+    let mut group = c.benchmark_group("push_pop_back");
+    group.bench_function("linked_list", |b| b.iter(push_pop_back::<LinkedList<u64>>));
+    group.bench_function("std_linked_list", |b| {
+        b.iter(push_pop_back::<StdLinkedList<u64>>)
+    });
+    group.bench_function("vec_deque", |b| b.iter(push_pop_back::<VecDeque<u64>>));
+    group.finish();
+}
+
+fn bench_iterate(c: &mut Criterion) {
+    fn run<D: Deque>(
+        group: &mut criterion::BenchmarkGroup<'_, criterion::measurement::WallTime>,
+        name: &str,
+    ) {
+        let deque = filled::<D>();
+        group.bench_function(name, |b| b.iter(|| black_box(deque.sum())));
+    }
+    let mut group = c.benchmark_group("iterate");
+    run::<LinkedList<u64>>(&mut group, "linked_list");
+    run::<StdLinkedList<u64>>(&mut group, "std_linked_list");
+    run::<VecDeque<u64>>(&mut group, "vec_deque");
+    group.finish();
+}
+
+fn bench_clone(c: &mut Criterion) {
+    fn run<D: Deque>(
+        group: &mut criterion::BenchmarkGroup<'_, criterion::measurement::WallTime>,
+        name: &str,
+    ) {
+        let deque = filled::<D>();
+        group.bench_function(name, |b| b.iter(|| black_box(deque.clone())));
+    }
+    let mut group = c.benchmark_group("clone");
+    run::<LinkedList<u64>>(&mut group, "linked_list");
+    run::<StdLinkedList<u64>>(&mut group, "std_linked_list");
+    run::<VecDeque<u64>>(&mut group, "vec_deque");
+    group.finish();
+}
+
+// std's LinkedList has no sort, so it is left out of this group.
+fn bench_sort(c: &mut Criterion) {
+    let mut group = c.benchmark_group("sort");
+    let list = filled::<LinkedList<u64>>();
+    group.bench_function("linked_list", |b| {
+        b.iter_batched(
+            || list.clone(),
+            |mut list| list.sort(),
+            BatchSize::SmallInput,
+        )
+    });
+    let deque = filled::<VecDeque<u64>>();
+    group.bench_function("vec_deque", |b| {
+        b.iter_batched(
+            || deque.clone(),
+            |mut deque| deque.make_contiguous().sort(),
+            BatchSize::SmallInput,
+        )
+    });
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    bench_push_pop,
+    bench_iterate,
+    bench_clone,
+    bench_sort
+);
+criterion_main!(benches);
@@ -0,0 +1,37 @@
+//! Compares push/pop churn on a list backed by the global allocator with the
+//! same workload on a pooled list, where every node after warm-up is recycled.
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use linked_list::{LinkedList, Pool};
+
+const BATCH: usize = 1000;
+
+// Fills the list up and drains it again, many times over, so nearly all of the
+// time goes into allocating and freeing nodes.
+fn churn(list: &mut LinkedList<u64>) {
+    for _ in 0..10 {
+        for i in 0..BATCH as u64 {
+            list.push_back(i);
+        }
+        while let Some(value) = list.pop_front() {
+            black_box(value);
+        }
+    }
+}
+
+fn bench_churn(c: &mut Criterion) {
+    let mut group = c.benchmark_group("push_pop_churn");
+    group.bench_function("global_allocator", |b| {
+        let mut list = LinkedList::new();
+        b.iter(|| churn(&mut list));
+    });
+    group.bench_function("pool", |b| {
+        let pool = Pool::with_capacity(BATCH);
+        let mut list = LinkedList::with_pool(&pool);
+        b.iter(|| churn(&mut list));
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bench_churn);
+criterion_main!(benches);
@@ -0,0 +1,274 @@
+use crate::linked_list::LinkedList;
+use crossbeam_epoch::{self as epoch, Atomic, Owned};
+use std::mem::ManuallyDrop;
+use std::ptr;
+use std::sync::atomic::Ordering;
+use std::sync::{Arc, Mutex, MutexGuard};
+
+/// A lock-free stack (Treiber stack) that can be pushed to and popped from by
+/// many threads at once.
+///
+/// Popped nodes can't be freed right away because another thread may still be
+/// looking at them, so reclamation is deferred with crossbeam's epoch GC.
+pub struct Stack<T> {
+    head: Atomic<Node<T>>,
+}
+
+struct Node<T> {
+    // Moved out with `ptr::read` when the node is popped, so the deferred
+    // destructor must not drop it again.
+    value: ManuallyDrop<T>,
+    next: Atomic<Node<T>>,
+}
+
+// Values are only ever moved in and out of the stack, never shared, so the
+// stack is safe to use from any thread as long as the values can be sent.
+unsafe impl<T: Send> Send for Stack<T> {}
+unsafe impl<T: Send> Sync for Stack<T> {}
+
+impl<T> Stack<T> {
+    pub fn new() -> Stack<T> {
+        Stack {
+            head: Atomic::null(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        let guard = epoch::pin();
+        self.head.load(Ordering::Acquire, &guard).is_null()
+    }
+
+    pub fn push(&self, value: T) {
+        let mut node = Owned::new(Node {
+            value: ManuallyDrop::new(value),
+            next: Atomic::null(),
+        });
+        let guard = epoch::pin();
+        loop {
+            let head = self.head.load(Ordering::Relaxed, &guard);
+            node.next.store(head, Ordering::Relaxed);
+            match self.head.compare_exchange(
+                head,
+                node,
+                Ordering::Release,
+                Ordering::Relaxed,
+                &guard,
+            ) {
+                Ok(_) => return,
+                Err(err) => node = err.new,
+            }
+        }
+    }
+
+    pub fn pop(&self) -> Option<T> {
+        let guard = epoch::pin();
+        loop {
+            let head = self.head.load(Ordering::Acquire, &guard);
+            // Safety: nodes reachable from `head` are only destroyed after
+            // every thread pinned at the time of the pop has unpinned.
+            let node = unsafe { head.as_ref() }?;
+            let next = node.next.load(Ordering::Relaxed, &guard);
+            if self
+                .head
+                .compare_exchange(head, next, Ordering::Relaxed, Ordering::Relaxed, &guard)
+                .is_ok()
+            {
+                // Safety: the CAS unlinked the node, so we are the only thread
+                // that will take its value, and nobody can reach it anymore
+                // once the current epoch ends.
+                unsafe {
+                    guard.defer_destroy(head);
+                    return Some(ManuallyDrop::into_inner(ptr::read(&node.value)));
+                }
+            }
+        }
+    }
+}
+
+impl<T> Default for Stack<T> {
+    fn default() -> Stack<T> {
+        Stack::new()
+    }
+}
+
+impl<T> Drop for Stack<T> {
+    fn drop(&mut self) {
+        while self.pop().is_some() {}
+    }
+}
+
+/// A `LinkedList` behind a mutex that can be cloned and handed to other
+/// threads. Clones share the same underlying list.
+pub struct SyncLinkedList<T> {
+    inner: Arc<Mutex<LinkedList<T>>>,
+}
+
+impl<T> SyncLinkedList<T> {
+    pub fn new() -> SyncLinkedList<T> {
+        SyncLinkedList {
+            inner: Arc::new(Mutex::new(LinkedList::new())),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.lock().get_size()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.lock().is_empty()
+    }
+
+    pub fn push_front(&self, value: T) {
+        self.lock().push_front(value);
+    }
+
+    pub fn pop_front(&self) -> Option<T> {
+        self.lock().pop_front()
+    }
+
+    /// Copies the current contents out, front to back, while holding the lock
+    /// once, so the result is a consistent snapshot.
+    pub fn to_vec(&self) -> Vec<T>
+    where
+        T: Clone,
+    {
+        self.lock().iter().cloned().collect()
+    }
+
+    fn lock(&self) -> MutexGuard<'_, LinkedList<T>> {
+        self.inner
+            .lock()
+            .expect("another thread panicked while holding the list lock")
+    }
+}
+
+impl<T> Clone for SyncLinkedList<T> {
+    fn clone(&self) -> SyncLinkedList<T> {
+        SyncLinkedList {
+            inner: Arc::clone(&self.inner),
+        }
+    }
+}
+
+impl<T> Default for SyncLinkedList<T> {
+    fn default() -> SyncLinkedList<T> {
+        SyncLinkedList::new()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::sync::Arc;
+    use std::thread;
+
+    #[test]
+    fn test_push_pop() {
+        let stack = Stack::new();
+        assert!(stack.is_empty());
+        assert_eq!(stack.pop(), None);
+        stack.push(1);
+        stack.push(2);
+        assert!(!stack.is_empty());
+        assert_eq!(stack.pop(), Some(2));
+        assert_eq!(stack.pop(), Some(1));
+        assert_eq!(stack.pop(), None);
+    }
+
+    #[test]
+    fn test_concurrent_push_pop() {
+        const THREADS: usize = 8;
+        const PER_THREAD: usize = 1000;
+        let stack = Arc::new(Stack::new());
+        let handles: Vec<_> = (0..THREADS)
+            .map(|t| {
+                let stack = Arc::clone(&stack);
+                thread::spawn(move || {
+                    let mut popped = Vec::new();
+                    for i in 0..PER_THREAD {
+                        stack.push(t * PER_THREAD + i);
+                        if i % 2 == 0 {
+                            popped.extend(stack.pop());
+                        }
+                    }
+                    popped
+                })
+            })
+            .collect();
+        let mut seen: Vec<usize> = handles
+            .into_iter()
+            .flat_map(|handle| handle.join().unwrap())
+            .collect();
+        while let Some(value) = stack.pop() {
+            seen.push(value);
+        }
+        seen.sort_unstable();
+        assert_eq!(seen, (0..THREADS * PER_THREAD).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_sync_list_basics() {
+        let list = SyncLinkedList::new();
+        assert!(list.is_empty());
+        list.push_front(2);
+        list.push_front(1);
+        let shared = list.clone();
+        assert_eq!(shared.len(), 2);
+        assert_eq!(shared.to_vec(), vec![1, 2]);
+        assert_eq!(shared.pop_front(), Some(1));
+        assert_eq!(list.len(), 1);
+    }
+
+    #[test]
+    fn test_sync_list_many_threads() {
+        const PRODUCERS: usize = 4;
+        const PER_PRODUCER: usize = 500;
+        let list = SyncLinkedList::new();
+        let producers: Vec<_> = (0..PRODUCERS)
+            .map(|p| {
+                let list = list.clone();
+                thread::spawn(move || {
+                    for i in 0..PER_PRODUCER {
+                        list.push_front(p * PER_PRODUCER + i);
+                    }
+                })
+            })
+            .collect();
+        let consumers: Vec<_> = (0..PRODUCERS)
+            .map(|_| {
+                let list = list.clone();
+                thread::spawn(move || {
+                    let mut taken = Vec::new();
+                    for _ in 0..PER_PRODUCER / 2 {
+                        taken.extend(list.pop_front());
+                        assert!(list.to_vec().len() <= PRODUCERS * PER_PRODUCER);
+                    }
+                    taken
+                })
+            })
+            .collect();
+        for producer in producers {
+            producer.join().unwrap();
+        }
+        let mut seen: Vec<usize> = consumers
+            .into_iter()
+            .flat_map(|consumer| consumer.join().unwrap())
+            .collect();
+        assert_eq!(seen.len() + list.len(), PRODUCERS * PER_PRODUCER);
+        seen.extend(list.to_vec());
+        seen.sort_unstable();
+        assert_eq!(seen, (0..PRODUCERS * PER_PRODUCER).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_drop_frees_values() {
+        let value = Arc::new(());
+        let stack = Stack::new();
+        for _ in 0..10 {
+            stack.push(Arc::clone(&value));
+        }
+        drop(stack.pop());
+        drop(stack);
+        assert_eq!(Arc::strong_count(&value), 1);
+    }
+}
@@ -0,0 +1,357 @@
+//! An intrusive doubly linked list.
+//!
+//! Instead of allocating a node per element, every element embeds a `Link`
+//! field and the list threads through those links directly. This is how kernels
+//! and allocators keep objects on several lists at once without allocating:
+//! give the type one `Link` per list it can be on, and declare an `Adapter`
+//! for each with `intrusive_adapter!`.
+//!
+//! The list borrows its elements for its whole lifetime, so they can neither
+//! move nor be dropped while linked, and dropping the list unlinks them again.
+//!
+//! ```
+//! use linked_list::intrusive::{IntrusiveList, Link};
+//! use linked_list::intrusive_adapter;
+//!
+//! struct Task {
+//!     id: u32,
+//!     link: Link,
+//! }
+//!
+//! intrusive_adapter!(TaskAdapter = Task { link });
+//!
+//! let a = Task { id: 1, link: Link::new() };
+//! let b = Task { id: 2, link: Link::new() };
+//! let mut queue = IntrusiveList::<TaskAdapter>::new();
+//! queue.push_back(&a);
+//! queue.push_back(&b);
+//! assert!(queue.remove(&a));
+//! assert_eq!(queue.pop_front().map(|task| task.id), Some(2));
+//! ```
+
+use core::cell::Cell;
+use core::marker::PhantomData;
+use core::ptr::NonNull;
+use core::sync::atomic::{AtomicUsize, Ordering};
+
+/// The hook an element embeds for each list it can be on.
+pub struct Link {
+    // Neighbouring elements (not their links), type-erased so that `Link`
+    // doesn't need a type parameter.
+    prev: Cell<Option<NonNull<()>>>,
+    next: Cell<Option<NonNull<()>>>,
+    // Id of the list this link is on, or 0 when it isn't on any.
+    owner: Cell<usize>,
+}
+
+impl Link {
+    pub const fn new() -> Link {
+        Link {
+            prev: Cell::new(None),
+            next: Cell::new(None),
+            owner: Cell::new(0),
+        }
+    }
+
+    pub fn is_linked(&self) -> bool {
+        self.owner.get() != 0
+    }
+}
+
+impl Default for Link {
+    fn default() -> Link {
+        Link::new()
+    }
+}
+
+/// Tells a list how to find its `Link` inside an element. Implement it with
+/// `intrusive_adapter!` rather than by hand.
+///
+/// # Safety
+///
+/// `link` must always return the same `Link`, and that link must be a field of
+/// `item` that no other adapter uses.
+pub unsafe trait Adapter {
+    type Item;
+
+    fn link(item: &Self::Item) -> &Link;
+}
+
+/// Declares an `Adapter` for the `Link` field `$field` of `$item`:
+/// `intrusive_adapter!(pub MyAdapter = MyType { link_field });`
+#[macro_export]
+macro_rules! intrusive_adapter {
+    ($vis:vis $name:ident = $item:ty { $field:ident }) => {
+        $vis struct $name;
+
+        unsafe impl $crate::intrusive::Adapter for $name {
+            type Item = $item;
+
+            fn link(item: &$item) -> &$crate::intrusive::Link {
+                &item.$field
+            }
+        }
+    };
+}
+
+// Gives every list a distinct, never reused id, so a link can tell in O(1)
+// which list (if any) it is on.
+static NEXT_LIST_ID: AtomicUsize = AtomicUsize::new(1);
+
+pub struct IntrusiveList<'a, A: Adapter> {
+    head: Option<NonNull<A::Item>>,
+    tail: Option<NonNull<A::Item>>,
+    len: usize,
+    id: usize,
+    marker: PhantomData<&'a A::Item>,
+}
+
+// Safety (for every method below): `head`, `tail` and the pointers in the
+// links of linked elements all come from `&'a A::Item` references, so they
+// stay valid for as long as the list exists.
+impl<'a, A: Adapter> IntrusiveList<'a, A> {
+    pub fn new() -> IntrusiveList<'a, A> {
+        IntrusiveList {
+            head: None,
+            tail: None,
+            len: 0,
+            id: NEXT_LIST_ID.fetch_add(1, Ordering::Relaxed),
+            marker: PhantomData,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns true if `item` is on this list. O(1).
+    pub fn contains(&self, item: &A::Item) -> bool {
+        A::link(item).owner.get() == self.id
+    }
+
+    /// Panics if `item` is already on a list through this adapter's link.
+    pub fn push_front(&mut self, item: &'a A::Item) {
+        let node = self.claim(item);
+        let link = A::link(item);
+        link.next.set(self.head.map(NonNull::cast));
+        match self.head {
+            Some(head) => unsafe { A::link(head.as_ref()).prev.set(Some(node.cast())) },
+            None => self.tail = Some(node),
+        }
+        self.head = Some(node);
+    }
+
+    /// Panics if `item` is already on a list through this adapter's link.
+    pub fn push_back(&mut self, item: &'a A::Item) {
+        let node = self.claim(item);
+        let link = A::link(item);
+        link.prev.set(self.tail.map(NonNull::cast));
+        match self.tail {
+            Some(tail) => unsafe { A::link(tail.as_ref()).next.set(Some(node.cast())) },
+            None => self.head = Some(node),
+        }
+        self.tail = Some(node);
+    }
+
+    pub fn pop_front(&mut self) -> Option<&'a A::Item> {
+        let item = unsafe { self.head?.as_ref() };
+        self.unlink(item);
+        Some(item)
+    }
+
+    pub fn pop_back(&mut self) -> Option<&'a A::Item> {
+        let item = unsafe { self.tail?.as_ref() };
+        self.unlink(item);
+        Some(item)
+    }
+
+    /// Unlinks `item` in O(1). Returns false (and does nothing) if it is not
+    /// on this list.
+    pub fn remove(&mut self, item: &A::Item) -> bool {
+        if !self.contains(item) {
+            return false;
+        }
+        self.unlink(item);
+        true
+    }
+
+    pub fn front(&self) -> Option<&'a A::Item> {
+        self.head.map(|node| unsafe { node.as_ref() })
+    }
+
+    pub fn back(&self) -> Option<&'a A::Item> {
+        self.tail.map(|node| unsafe { node.as_ref() })
+    }
+
+    pub fn iter(&self) -> Iter<'_, A> {
+        Iter {
+            next: self.head,
+            marker: PhantomData,
+        }
+    }
+
+    // Marks `item` as being on this list and returns a pointer to it, with
+    // both of its neighbours cleared.
+    fn claim(&mut self, item: &'a A::Item) -> NonNull<A::Item> {
+        let link = A::link(item);
+        assert!(!link.is_linked(), "item is already on a list");
+        link.owner.set(self.id);
+        link.prev.set(None);
+        link.next.set(None);
+        self.len += 1;
+        NonNull::from(item)
+    }
+
+    // Unlinks an item that is known to be on this list.
+    fn unlink(&mut self, item: &A::Item) {
+        let link = A::link(item);
+        let prev = link.prev.take().map(NonNull::cast::<A::Item>);
+        let next = link.next.take().map(NonNull::cast::<A::Item>);
+        match prev {
+            Some(prev) => unsafe { A::link(prev.as_ref()).next.set(next.map(NonNull::cast)) },
+            None => self.head = next,
+        }
+        match next {
+            Some(next) => unsafe { A::link(next.as_ref()).prev.set(prev.map(NonNull::cast)) },
+            None => self.tail = prev,
+        }
+        link.owner.set(0);
+        self.len -= 1;
+    }
+}
+
+impl<'a, A: Adapter> Default for IntrusiveList<'a, A> {
+    fn default() -> IntrusiveList<'a, A> {
+        IntrusiveList::new()
+    }
+}
+
+impl<'a, A: Adapter> Drop for IntrusiveList<'a, A> {
+    fn drop(&mut self) {
+        // Leave the elements unlinked so they can go on another list.
+        while self.pop_front().is_some() {}
+    }
+}
+
+pub struct Iter<'l, A: Adapter> {
+    next: Option<NonNull<A::Item>>,
+    marker: PhantomData<&'l A::Item>,
+}
+
+impl<'l, A: Adapter> Iterator for Iter<'l, A> {
+    type Item = &'l A::Item;
+
+    fn next(&mut self) -> Option<&'l A::Item> {
+        let item = unsafe { self.next?.as_ref() };
+        self.next = A::link(item).next.get().map(NonNull::cast);
+        Some(item)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    struct Task {
+        id: u32,
+        run_link: Link,
+        all_link: Link,
+    }
+
+    impl Task {
+        fn new(id: u32) -> Task {
+            Task {
+                id,
+                run_link: Link::new(),
+                all_link: Link::new(),
+            }
+        }
+    }
+
+    intrusive_adapter!(RunQueue = Task { run_link });
+    intrusive_adapter!(AllTasks = Task { all_link });
+
+    fn ids<A: Adapter<Item = Task>>(list: &IntrusiveList<'_, A>) -> Vec<u32> {
+        list.iter().map(|task| task.id).collect()
+    }
+
+    #[test]
+    fn test_push_pop() {
+        let tasks: Vec<Task> = (0..4).map(Task::new).collect();
+        let mut list = IntrusiveList::<RunQueue>::new();
+        assert!(list.is_empty());
+        list.push_back(&tasks[1]);
+        list.push_back(&tasks[2]);
+        list.push_front(&tasks[0]);
+        list.push_back(&tasks[3]);
+        assert_eq!(ids(&list), vec![0, 1, 2, 3]);
+        assert_eq!(list.len(), 4);
+        assert_eq!(list.front().map(|t| t.id), Some(0));
+        assert_eq!(list.back().map(|t| t.id), Some(3));
+        assert_eq!(list.pop_back().map(|t| t.id), Some(3));
+        assert_eq!(list.pop_front().map(|t| t.id), Some(0));
+        assert!(!tasks[0].run_link.is_linked());
+        assert_eq!(ids(&list), vec![1, 2]);
+    }
+
+    #[test]
+    fn test_two_lists_at_once() {
+        let tasks: Vec<Task> = (0..5).map(Task::new).collect();
+        let mut all = IntrusiveList::<AllTasks>::new();
+        let mut runnable = IntrusiveList::<RunQueue>::new();
+        for task in &tasks {
+            all.push_back(task);
+            if task.id % 2 == 0 {
+                runnable.push_back(task);
+            }
+        }
+        assert!(runnable.remove(&tasks[2]));
+        assert!(!runnable.remove(&tasks[1]));
+        assert!(all.contains(&tasks[2]));
+        assert!(!runnable.contains(&tasks[2]));
+        assert_eq!(ids(&runnable), vec![0, 4]);
+        assert!(all.remove(&tasks[4]));
+        assert_eq!(ids(&all), vec![0, 1, 2, 3]);
+        assert_eq!(ids(&runnable), vec![0, 4]);
+    }
+
+    #[test]
+    fn test_remove_from_wrong_list() {
+        let task = Task::new(0);
+        let mut a = IntrusiveList::<RunQueue>::new();
+        let mut b = IntrusiveList::<RunQueue>::new();
+        a.push_back(&task);
+        assert!(!b.remove(&task));
+        assert_eq!(a.len(), 1);
+        assert!(a.remove(&task));
+        assert!(a.is_empty());
+        assert_eq!(a.front().map(|t| t.id), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "already on a list")]
+    fn test_push_linked_item() {
+        let task = Task::new(0);
+        let mut a = IntrusiveList::<RunQueue>::new();
+        let mut b = IntrusiveList::<RunQueue>::new();
+        a.push_back(&task);
+        b.push_back(&task);
+    }
+
+    #[test]
+    fn test_drop_unlinks() {
+        let task = Task::new(0);
+        {
+            let mut list = IntrusiveList::<RunQueue>::new();
+            list.push_back(&task);
+        }
+        assert!(!task.run_link.is_linked());
+        let mut other = IntrusiveList::<RunQueue>::new();
+        other.push_front(&task);
+        assert_eq!(ids(&other), vec![0]);
+    }
+}
@@ -0,0 +1,23 @@
+//! Linked lists and a few relatives.
+//!
+//! Everything except the `concurrent` and `pool` modules and `ComputeNorm` only
+//! needs `alloc`, so with `default-features = false` the crate builds as
+//! `no_std`.
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+
+extern crate alloc;
+
+#[cfg(feature = "std")]
+pub mod concurrent;
+pub mod intrusive;
+pub mod linked_list;
+pub mod persistent_list;
+#[cfg(feature = "std")]
+pub mod pool;
+
+#[cfg(feature = "std")]
+pub use crate::linked_list::ComputeNorm;
+pub use crate::linked_list::LinkedList;
+pub use crate::persistent_list::PersistentList;
+#[cfg(feature = "std")]
+pub use crate::pool::Pool;
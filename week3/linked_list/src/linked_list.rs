@@ -1,36 +1,92 @@
-use std::option::Option;
-use std::fmt;
+use alloc::boxed::Box;
+use alloc::string::String;
+use core::cmp::Ordering;
+use core::fmt;
+use core::iter::FromIterator;
+use core::marker::PhantomData;
+use core::mem;
+use core::ops::{Add, Index, IndexMut};
+use core::ptr::NonNull;
 
+#[cfg(feature = "std")]
+use crate::pool::{Pool, PoolCache};
+
+// `f64::sqrt` needs std, so norms are not available in `no_std` builds.
+#[cfg(feature = "std")]
 pub trait ComputeNorm {
     fn compute_norm(&self) -> f64 {
         0.0
     }
 }
 
+/// A doubly linked list.
+///
+/// Nodes are individually heap allocated and linked with raw `NonNull`
+/// pointers, which gives O(1) access to both ends and lets whole chains be
+/// spliced between lists without touching the elements. All of the unsafe code
+/// lives in this module behind a safe API.
 pub struct LinkedList<T> {
-    head: Option<Box<Node<T>>>,
+    head: Link<T>,
+    tail: Link<T>,
     size: usize,
+    // Where nodes come from and go back to, if not the global allocator.
+    #[cfg(feature = "std")]
+    pool: Option<PoolCache<T>>,
+    // We own the nodes (and so the `T`s), which matters for the drop checker.
+    marker: PhantomData<Box<Node<T>>>,
 }
 
-struct Node<T> {
+type Link<T> = Option<NonNull<Node<T>>>;
+
+pub(crate) struct Node<T> {
     value: T,
-    next: Option<Box<Node<T>>>,
+    prev: Link<T>,
+    next: Link<T>,
 }
 
+// The list owns its nodes outright, so sending or sharing a list is exactly as
+// safe as sending or sharing its elements.
+unsafe impl<T: Send> Send for LinkedList<T> {}
+unsafe impl<T: Sync> Sync for LinkedList<T> {}
+
 impl<T> Node<T> {
-    pub fn new(value: T, next: Option<Box<Node<T>>>) -> Node<T> {
-        Node {
-            value: value,
-            next: next,
-        }
+    // Allocates a detached node. Ownership of the allocation passes to
+    // whichever list it is linked into, and is taken back in
+    // `LinkedList::free_node`.
+    fn new_raw(value: T) -> NonNull<Node<T>> {
+        NonNull::from(Box::leak(Box::new(Node {
+            value,
+            prev: None,
+            next: None,
+        })))
     }
 }
 
+// Invariants, relied on by every `unsafe` block in this file: `head` and
+// `tail` are both `None` or both point at nodes owned by the list, following
+// `next` from `head` (or `prev` from `tail`) visits exactly `size` distinct
+// nodes, and each node's `prev`/`next` agree with its neighbours.
 impl<T> LinkedList<T> {
     pub fn new() -> LinkedList<T> {
         LinkedList {
             head: None,
+            tail: None,
             size: 0,
+            #[cfg(feature = "std")]
+            pool: None,
+            marker: PhantomData,
+        }
+    }
+
+    /// Creates an empty list that takes its nodes from `pool` and returns
+    /// them there when elements are removed, instead of going through the
+    /// global allocator for every push and pop. Lists split off this one share
+    /// the same pool.
+    #[cfg(feature = "std")]
+    pub fn with_pool(pool: &Pool<T>) -> LinkedList<T> {
+        LinkedList {
+            pool: Some(PoolCache::new(pool)),
+            ..LinkedList::new()
         }
     }
 
@@ -43,79 +99,2203 @@ impl<T> LinkedList<T> {
     }
 
     pub fn push_front(&mut self, value: T) {
-        let new_node = Box::new(Node::new(value, self.head.take()));
-        self.head = Some(new_node);
-        self.size += 1;
+        let head = self.head;
+        let node = self.alloc_node(value);
+        unsafe { self.link_before(node, head) }
     }
 
     pub fn pop_front(&mut self) -> Option<T> {
-        let node = self.head.take()?;
-        self.head = node.next;
-        self.size -= 1;
-        Some(node.value)
+        let head = self.head?;
+        Some(unsafe { self.unlink(head) })
     }
-}
 
-impl<T: fmt::Display> fmt::Display for LinkedList<T> {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let mut current: &Option<Box<Node<T>>> = &self.head;
+    /// Appends `value` to the end of the list in O(1) using the tail pointer.
+    pub fn push_back(&mut self, value: T) {
+        let node = self.alloc_node(value);
+        unsafe { self.link_before(node, None) }
+    }
+
+    pub fn pop_back(&mut self) -> Option<T> {
+        let tail = self.tail?;
+        Some(unsafe { self.unlink(tail) })
+    }
+
+    /// Shortens the list to its first `len` elements, dropping the rest one
+    /// node at a time from the back. Does nothing if the list is already that
+    /// short.
+    pub fn truncate(&mut self, len: usize) {
+        while self.size > len {
+            self.pop_back();
+        }
+    }
+
+    /// Removes and drops every element, one node at a time so that long lists
+    /// can't overflow the stack. The list stays usable (and keeps its pool).
+    pub fn clear(&mut self) {
+        while self.pop_front().is_some() {}
+    }
+
+    /// Moves all of `other`'s nodes onto the end of this list, leaving `other`
+    /// empty. Nothing is copied, so this is O(1).
+    pub fn append(&mut self, other: &mut LinkedList<T>) {
+        let tail = match self.tail {
+            Some(tail) => tail,
+            None => {
+                // Take over other's nodes, but each list keeps its own pool.
+                mem::swap(&mut self.head, &mut other.head);
+                mem::swap(&mut self.tail, &mut other.tail);
+                mem::swap(&mut self.size, &mut other.size);
+                return;
+            }
+        };
+        let other_head = match other.head.take() {
+            Some(head) => head,
+            None => return,
+        };
+        unsafe {
+            (*tail.as_ptr()).next = Some(other_head);
+            (*other_head.as_ptr()).prev = Some(tail);
+        }
+        self.tail = other.tail.take();
+        self.size += mem::replace(&mut other.size, 0);
+    }
+
+    pub fn front(&self) -> Option<&T> {
+        self.head.map(|node| unsafe { &(*node.as_ptr()).value })
+    }
+
+    pub fn front_mut(&mut self) -> Option<&mut T> {
+        self.head.map(|node| unsafe { &mut (*node.as_ptr()).value })
+    }
+
+    pub fn peek_front(&self) -> Option<&T> {
+        self.front()
+    }
+
+    pub fn peek_front_mut(&mut self) -> Option<&mut T> {
+        self.front_mut()
+    }
+
+    pub fn peek_back(&self) -> Option<&T> {
+        self.tail.map(|node| unsafe { &(*node.as_ptr()).value })
+    }
+
+    pub fn peek_back_mut(&mut self) -> Option<&mut T> {
+        self.tail.map(|node| unsafe { &mut (*node.as_ptr()).value })
+    }
+
+    /// Returns a reference to the element at position `index`. This walks the
+    /// list from whichever end is closer, so it is O(n); use `front` or
+    /// `peek_back` for the ends.
+    pub fn get(&self, index: usize) -> Option<&T> {
+        let node = self.node_at(index)?;
+        Some(unsafe { &(*node.as_ptr()).value })
+    }
+
+    /// Mutable version of `get`. Also O(n).
+    pub fn get_mut(&mut self, index: usize) -> Option<&mut T> {
+        let node = self.node_at(index)?;
+        Some(unsafe { &mut (*node.as_ptr()).value })
+    }
+
+    /// Returns the element `n` places before the end (`n == 0` is the last
+    /// element), in a single pass with a leading and a trailing pointer.
+    pub fn nth_from_end(&self, n: usize) -> Option<&T> {
+        unsafe {
+            let mut lead = self.head;
+            for _ in 0..n {
+                lead = (*lead?.as_ptr()).next;
+            }
+            let mut lead = lead?;
+            let mut trail = self.head?;
+            while let Some(next) = (*lead.as_ptr()).next {
+                lead = next;
+                trail = (*trail.as_ptr()).next?;
+            }
+            Some(&(*trail.as_ptr()).value)
+        }
+    }
+
+    /// Mutable version of `nth_from_end`. Two pointers into the list can't
+    /// coexist with a mutable one, so this uses the tracked size instead.
+    pub fn nth_from_end_mut(&mut self, n: usize) -> Option<&mut T> {
+        let index = self.size.checked_sub(n)?.checked_sub(1)?;
+        self.get_mut(index)
+    }
+
+    pub fn contains(&self, value: &T) -> bool
+    where
+        T: PartialEq,
+    {
+        self.iter().any(|v| v == value)
+    }
+
+    /// Returns the first element for which `predicate` returns true.
+    pub fn find<P>(&self, mut predicate: P) -> Option<&T>
+    where
+        P: FnMut(&T) -> bool,
+    {
+        self.iter().find(|v| predicate(v))
+    }
+
+    /// Returns the index of the first element for which `predicate` returns
+    /// true.
+    pub fn position<P>(&self, predicate: P) -> Option<usize>
+    where
+        P: FnMut(&T) -> bool,
+    {
+        self.iter().position(predicate)
+    }
+
+    /// Returns a new list holding `f` applied to every element, in order.
+    pub fn map<U, F>(&self, f: F) -> LinkedList<U>
+    where
+        F: FnMut(&T) -> U,
+    {
+        self.iter().map(f).collect()
+    }
+
+    /// Returns a new list with clones of the elements for which `predicate`
+    /// returns true, in order. See `retain` for the in-place version.
+    pub fn filter<P>(&self, mut predicate: P) -> LinkedList<T>
+    where
+        T: Clone,
+        P: FnMut(&T) -> bool,
+    {
+        self.iter().filter(|v| predicate(v)).cloned().collect()
+    }
+
+    /// Pairs up the elements of both lists in order, stopping at the end of
+    /// the shorter one. Leftover elements of the longer list are dropped.
+    pub fn zip<U>(mut self, mut other: LinkedList<U>) -> LinkedList<(T, U)> {
+        let mut zipped = LinkedList::new();
+        while let (Some(a), Some(b)) = (self.pop_front(), other.pop_front()) {
+            zipped.push_back((a, b));
+        }
+        zipped
+    }
+
+    /// Splits the list into the elements for which `predicate` returns true
+    /// and those for which it returns false, keeping their relative order.
+    /// The nodes are relinked into the two lists, so nothing is cloned or
+    /// reallocated.
+    pub fn partition<P>(mut self, mut predicate: P) -> (LinkedList<T>, LinkedList<T>)
+    where
+        P: FnMut(&T) -> bool,
+    {
+        let mut matching = self.new_sibling();
+        let mut rest = self.new_sibling();
+        while let Some(node) = self.head {
+            unsafe {
+                let target = if predicate(&(*node.as_ptr()).value) {
+                    &mut matching
+                } else {
+                    &mut rest
+                };
+                self.detach(node);
+                target.link_before(node, None);
+            }
+        }
+        (matching, rest)
+    }
+
+    /// Keeps only the elements for which `keep` returns true, unlinking the
+    /// rest in a single pass.
+    pub fn retain<F>(&mut self, mut keep: F)
+    where
+        F: FnMut(&T) -> bool,
+    {
+        let mut current = self.head;
+        while let Some(node) = current {
+            unsafe {
+                current = (*node.as_ptr()).next;
+                if !keep(&(*node.as_ptr()).value) {
+                    self.unlink(node);
+                }
+            }
+        }
+    }
+
+    /// Removes consecutive repeated elements, keeping the first of each run.
+    pub fn dedup(&mut self)
+    where
+        T: PartialEq,
+    {
+        self.dedup_by(|a, b| a == b);
+    }
+
+    /// Removes consecutive elements that map to the same key.
+    pub fn dedup_by_key<K, F>(&mut self, mut key: F)
+    where
+        K: PartialEq,
+        F: FnMut(&T) -> K,
+    {
+        self.dedup_by(|a, b| key(a) == key(b));
+    }
+
+    /// Removes every element for which `same(element, previous_kept)` returns
+    /// true, in a single pass.
+    pub fn dedup_by<F>(&mut self, mut same: F)
+    where
+        F: FnMut(&T, &T) -> bool,
+    {
+        let mut current = self.head;
+        while let Some(node) = current {
+            unsafe {
+                match (*node.as_ptr()).next {
+                    Some(next) if same(&(*next.as_ptr()).value, &(*node.as_ptr()).value) => {
+                        self.unlink(next);
+                    }
+                    next => current = next,
+                }
+            }
+        }
+    }
+
+    /// Reverses the list in place by relinking the nodes; nothing is
+    /// allocated or moved.
+    pub fn reverse(&mut self) {
+        let mut current = self.head;
+        while let Some(node) = current {
+            unsafe {
+                let node = &mut *node.as_ptr();
+                mem::swap(&mut node.prev, &mut node.next);
+                current = node.prev;
+            }
+        }
+        mem::swap(&mut self.head, &mut self.tail);
+    }
+
+    /// Sorts the list in ascending order. This is a stable merge sort that
+    /// relinks the existing nodes, so it runs in O(n log n) without any
+    /// auxiliary buffer.
+    pub fn sort(&mut self)
+    where
+        T: Ord,
+    {
+        self.sort_by(|a, b| a.cmp(b));
+    }
+
+    /// Like `sort`, but orders elements with the given comparator. If
+    /// `compare` panics, the elements are leaked and the list is left empty.
+    pub fn sort_by<F>(&mut self, mut compare: F)
+    where
+        F: FnMut(&T, &T) -> Ordering,
+    {
+        // Detach the chain first so a panicking comparator can't leave the
+        // list pointing at half-merged nodes.
+        let head = self.head.take();
+        let size = mem::replace(&mut self.size, 0);
+        self.tail = None;
+        unsafe {
+            let head = merge_sort(head, size, &mut compare);
+            self.adopt_chain(head, size);
+        }
+    }
+
+    /// Merges two lists that are already sorted into one sorted list, in a
+    /// single pass that relinks the nodes. Equal elements from `self` come
+    /// before those from `other`.
+    pub fn merge_sorted(self, other: LinkedList<T>) -> LinkedList<T>
+    where
+        T: Ord,
+    {
+        self.merge_sorted_by(other, |a, b| a.cmp(b))
+    }
+
+    /// Like `merge_sorted`, but with the given comparator, which both lists
+    /// must already be sorted by. If `compare` panics, the elements are
+    /// leaked.
+    pub fn merge_sorted_by<F>(mut self, mut other: LinkedList<T>, mut compare: F) -> LinkedList<T>
+    where
+        F: FnMut(&T, &T) -> Ordering,
+    {
+        let a = self.head.take();
+        let b = other.head.take();
+        let size = mem::replace(&mut self.size, 0) + mem::replace(&mut other.size, 0);
+        self.tail = None;
+        other.tail = None;
+        unsafe {
+            let head = merge(a, b, &mut compare);
+            self.adopt_chain(head, size);
+        }
+        self
+    }
+
+    /// Returns a cursor positioned on the first element, which can walk the
+    /// list and insert or remove elements around its position in O(1).
+    pub fn cursor_front_mut(&mut self) -> CursorMut<'_, T> {
+        CursorMut {
+            current: self.head,
+            index: 0,
+            list: self,
+        }
+    }
+
+    /// Returns an iterator that removes and yields every element for which
+    /// `predicate` returns true. Elements the iterator hasn't reached yet stay
+    /// in the list if it is dropped early.
+    pub fn extract_if<F>(&mut self, predicate: F) -> ExtractIf<'_, T, F>
+    where
+        F: FnMut(&mut T) -> bool,
+    {
+        ExtractIf {
+            cursor: self.cursor_front_mut(),
+            predicate,
+        }
+    }
+
+    /// Formats every element and joins them with `sep`, e.g. `"1, 2, 3"`.
+    pub fn join(&self, sep: &str) -> String
+    where
+        T: fmt::Display,
+    {
         let mut result = String::new();
-        loop {
-            match current {
-                Some(node) => {
-                    result = format!("{} {}", result, node.value);
-                    current = &node.next;
+        self.write_joined(&mut result, sep)
+            .expect("writing to a String cannot fail");
+        result
+    }
+
+    // Streams the elements straight into `out` with `sep` between them.
+    fn write_joined<W: fmt::Write>(&self, out: &mut W, sep: &str) -> fmt::Result
+    where
+        T: fmt::Display,
+    {
+        for (i, value) in self.iter().enumerate() {
+            if i > 0 {
+                out.write_str(sep)?;
+            }
+            write!(out, "{}", value)?;
+        }
+        Ok(())
+    }
+
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter {
+            head: self.head,
+            tail: self.tail,
+            len: self.size,
+            marker: PhantomData,
+        }
+    }
+
+    pub fn iter_mut(&mut self) -> IterMut<'_, T> {
+        IterMut {
+            head: self.head,
+            tail: self.tail,
+            len: self.size,
+            marker: PhantomData,
+        }
+    }
+
+    /// Splits the list in two at `at`: `self` keeps the first `at` elements
+    /// and the rest are returned as a new list.
+    ///
+    /// Panics if `at > len`.
+    pub fn split_off(&mut self, at: usize) -> LinkedList<T> {
+        assert!(
+            at <= self.size,
+            "split index (is {}) should be <= len (is {})",
+            at,
+            self.size
+        );
+        let mut other = self.new_sibling();
+        if at == 0 {
+            other.append(self);
+            return other;
+        }
+        let first = match self.node_at(at) {
+            Some(node) => node,
+            None => return other,
+        };
+        unsafe {
+            let last = (*first.as_ptr()).prev.take();
+            if let Some(last) = last {
+                (*last.as_ptr()).next = None;
+            }
+            other.head = Some(first);
+            other.tail = self.tail;
+            other.size = self.size - at;
+            self.tail = last;
+            self.size = at;
+            other
+        }
+    }
+
+    /// Swaps the elements at positions `i` and `j`.
+    ///
+    /// Panics if either index is out of bounds, just like `slice::swap`.
+    pub fn swap(&mut self, i: usize, j: usize) {
+        assert!(
+            i < self.size && j < self.size,
+            "swap indices ({}, {}) out of bounds for len {}",
+            i,
+            j,
+            self.size
+        );
+        if i == j {
+            return;
+        }
+        let a = self.node_at(i).unwrap();
+        let b = self.node_at(j).unwrap();
+        // Safety: `i != j`, so these are two distinct nodes.
+        unsafe { mem::swap(&mut (*a.as_ptr()).value, &mut (*b.as_ptr()).value) }
+    }
+
+    /// Rotates the list so that the first `n` elements move to the back. The
+    /// nodes are relinked, not moved.
+    ///
+    /// Panics if `n > len`.
+    pub fn rotate_left(&mut self, n: usize) {
+        assert!(
+            n <= self.size,
+            "rotation amount (is {}) should be <= len (is {})",
+            n,
+            self.size
+        );
+        if n == 0 || n == self.size {
+            return;
+        }
+        let mut back = self.split_off(n);
+        back.append(self);
+        mem::swap(self, &mut back);
+    }
+
+    /// Rotates the list so that the last `n` elements move to the front.
+    ///
+    /// Panics if `n > len`.
+    pub fn rotate_right(&mut self, n: usize) {
+        assert!(
+            n <= self.size,
+            "rotation amount (is {}) should be <= len (is {})",
+            n,
+            self.size
+        );
+        self.rotate_left(self.size - n);
+    }
+
+    /// Inserts `value` so that it ends up at position `index`, shifting every
+    /// element after it back by one.
+    ///
+    /// Panics if `index > len`, just like `Vec::insert`.
+    pub fn insert(&mut self, index: usize, value: T) {
+        assert!(
+            index <= self.size,
+            "insertion index (is {}) should be <= len (is {})",
+            index,
+            self.size
+        );
+        let before = self.node_at(index);
+        let node = self.alloc_node(value);
+        unsafe { self.link_before(node, before) }
+    }
+
+    /// Removes and returns the element at position `index`, or `None` if the
+    /// index is out of bounds.
+    pub fn remove(&mut self, index: usize) -> Option<T> {
+        let node = self.node_at(index)?;
+        Some(unsafe { self.unlink(node) })
+    }
+
+    /// Returns true if following `next` links from the head never reaches the
+    /// end. This can only happen if the list's own code has a bug, so it is
+    /// meant for tests and debugging. Uses Floyd's tortoise and hare, so it
+    /// takes O(n) time and O(1) space.
+    pub fn is_cyclic(&self) -> bool {
+        let mut slow = self.head;
+        let mut fast = self.head;
+        unsafe {
+            loop {
+                fast = match fast.and_then(|node| (*node.as_ptr()).next) {
+                    Some(node) => (*node.as_ptr()).next,
+                    None => return false,
+                };
+                slow = slow.and_then(|node| (*node.as_ptr()).next);
+                if fast.is_some() && fast == slow {
+                    return true;
+                }
+            }
+        }
+    }
+
+    /// Checks every structural invariant of the list and panics with a
+    /// description of the first one that is broken: no cycles, matching
+    /// `prev`/`next` links, correct head and tail, and a `size` that matches
+    /// the number of nodes. Intended for tests and debug builds.
+    pub fn debug_validate(&self) {
+        assert!(!self.is_cyclic(), "list contains a cycle");
+        assert_eq!(
+            self.head.is_none(),
+            self.tail.is_none(),
+            "head and tail disagree about whether the list is empty"
+        );
+        let mut count = 0;
+        let mut prev: Link<T> = None;
+        let mut current = self.head;
+        unsafe {
+            while let Some(node) = current {
+                assert!(
+                    (*node.as_ptr()).prev == prev,
+                    "node {} has a wrong prev link",
+                    count
+                );
+                count += 1;
+                prev = Some(node);
+                current = (*node.as_ptr()).next;
+            }
+        }
+        assert!(prev == self.tail, "tail is not the last node");
+        assert_eq!(count, self.size, "size does not match the node count");
+    }
+
+    // Finds the node at position `index`, walking from whichever end is
+    // closer. Returns `None` if `index >= size`.
+    fn node_at(&self, index: usize) -> Link<T> {
+        if index >= self.size {
+            return None;
+        }
+        unsafe {
+            if index < self.size / 2 {
+                let mut node = self.head?;
+                for _ in 0..index {
+                    node = (*node.as_ptr()).next?;
+                }
+                Some(node)
+            } else {
+                let mut node = self.tail?;
+                for _ in index + 1..self.size {
+                    node = (*node.as_ptr()).prev?;
+                }
+                Some(node)
+            }
+        }
+    }
+
+    // Links the detached `node` in front of `before`, or at the back if
+    // `before` is `None`.
+    //
+    // Safety: `node` must come from `alloc_node` and not be linked anywhere,
+    // and `before` must be `None` or a node of this list.
+    unsafe fn link_before(&mut self, node: NonNull<Node<T>>, before: Link<T>) {
+        let prev = match before {
+            Some(before) => (*before.as_ptr()).prev,
+            None => self.tail,
+        };
+        (*node.as_ptr()).prev = prev;
+        (*node.as_ptr()).next = before;
+        match prev {
+            Some(prev) => (*prev.as_ptr()).next = Some(node),
+            None => self.head = Some(node),
+        }
+        match before {
+            Some(before) => (*before.as_ptr()).prev = Some(node),
+            None => self.tail = Some(node),
+        }
+        self.size += 1;
+    }
+
+    // Unlinks `node`, frees it and returns its value.
+    //
+    // Safety: `node` must be a node of this list.
+    unsafe fn unlink(&mut self, node: NonNull<Node<T>>) -> T {
+        self.detach(node);
+        self.free_node(node)
+    }
+
+    // Unlinks `node` without freeing it, so it can be linked into another list.
+    //
+    // Safety: `node` must be a node of this list.
+    unsafe fn detach(&mut self, node: NonNull<Node<T>>) {
+        let (prev, next) = ((*node.as_ptr()).prev, (*node.as_ptr()).next);
+        match prev {
+            Some(prev) => (*prev.as_ptr()).next = next,
+            None => self.head = next,
+        }
+        match next {
+            Some(next) => (*next.as_ptr()).prev = prev,
+            None => self.tail = prev,
+        }
+        self.size -= 1;
+    }
+
+    // Makes the empty list own the chain starting at `head`, rebuilding the
+    // `prev` links and the tail, which the merge helpers don't maintain.
+    //
+    // Safety: the list must be empty and `head` must start a `None`-terminated
+    // chain of exactly `size` nodes that no list owns.
+    unsafe fn adopt_chain(&mut self, head: Link<T>, size: usize) {
+        let mut prev = None;
+        let mut current = head;
+        while let Some(node) = current {
+            (*node.as_ptr()).prev = prev;
+            prev = Some(node);
+            current = (*node.as_ptr()).next;
+        }
+        self.head = head;
+        self.tail = prev;
+        self.size = size;
+    }
+
+    // Returns an empty list that shares this list's pool, if it has one.
+    fn new_sibling(&self) -> LinkedList<T> {
+        LinkedList {
+            #[cfg(feature = "std")]
+            pool: self.pool.as_ref().map(|cache| PoolCache::new(cache.pool())),
+            ..LinkedList::new()
+        }
+    }
+
+    // Returns a detached node holding `value`, recycled from the pool when
+    // there is one with spare nodes.
+    fn alloc_node(&mut self, value: T) -> NonNull<Node<T>> {
+        #[cfg(feature = "std")]
+        {
+            if let Some(node) = self.pool.as_mut().and_then(PoolCache::take) {
+                // Safety: pooled nodes are valid allocations for a `Node<T>`
+                // whose contents are uninitialized.
+                unsafe {
+                    node.as_ptr().write(Node {
+                        value,
+                        prev: None,
+                        next: None,
+                    });
                 }
-                None => break,
+                return node;
+            }
+        }
+        Node::new_raw(value)
+    }
+
+    // Moves the value out of a detached node and releases the node, either to
+    // the pool or back to the global allocator.
+    //
+    // Safety: `node` must come from `alloc_node` and not be linked anywhere.
+    unsafe fn free_node(&mut self, node: NonNull<Node<T>>) -> T {
+        #[cfg(feature = "std")]
+        {
+            if let Some(pool) = &mut self.pool {
+                let value = core::ptr::read(&(*node.as_ptr()).value);
+                pool.give(node);
+                return value;
             }
         }
-        write!(f, "{}", result)
+        Box::from_raw(node.as_ptr()).value
     }
 }
 
-impl<T> Drop for LinkedList<T> {
-    fn drop(&mut self) {
-        let mut current = self.head.take();
-        while let Some(mut node) = current {
-            current = node.next.take();
+// Sorts the chain of `len` nodes starting at `head` by their `next` links and
+// returns the new head. `prev` links are left stale for the caller to fix.
+//
+// Safety: `head` must start a chain of exactly `len` valid nodes.
+unsafe fn merge_sort<T, F>(head: Link<T>, len: usize, compare: &mut F) -> Link<T>
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    if len <= 1 {
+        return head;
+    }
+    let mid = len / 2;
+    let mut last = head.unwrap();
+    for _ in 1..mid {
+        last = (*last.as_ptr()).next.unwrap();
+    }
+    let back = (*last.as_ptr()).next.take();
+    let front = merge_sort(head, mid, compare);
+    let back = merge_sort(back, len - mid, compare);
+    merge(front, back, compare)
+}
+
+// Merges two sorted chains into one. Ties are taken from `a` first so that the
+// sort stays stable.
+//
+// Safety: `a` and `b` must be separate, `None`-terminated chains.
+unsafe fn merge<T, F>(mut a: Link<T>, mut b: Link<T>, compare: &mut F) -> Link<T>
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    let mut head: Link<T> = None;
+    let mut tail: Link<T> = None;
+    while let (Some(x), Some(y)) = (a, b) {
+        let node = if compare(&(*x.as_ptr()).value, &(*y.as_ptr()).value) == Ordering::Greater {
+            b = (*y.as_ptr()).next;
+            y
+        } else {
+            a = (*x.as_ptr()).next;
+            x
+        };
+        match tail {
+            Some(tail) => (*tail.as_ptr()).next = Some(node),
+            None => head = Some(node),
         }
+        tail = Some(node);
     }
+    let rest = if a.is_some() { a } else { b };
+    match tail {
+        Some(tail) => (*tail.as_ptr()).next = rest,
+        None => head = rest,
+    }
+    head
 }
 
-impl<T: Clone> Clone for LinkedList<T> {
-    fn clone(&self) -> LinkedList<T> {
-        let mut elements: Vec<T> = Vec::new();
-        let mut current = &self.head;
-        loop {
-            match current {
-                Some(node) => {
-                    elements.insert(0, node.value.clone());
-                    current = &node.next;
-                }
-                None => break,
+/// A cursor that can edit the list around its position.
+///
+/// Besides the elements, the cursor can sit on a "ghost" position between the
+/// last and the first element (this is where it ends up after walking off
+/// either end, and where it starts on an empty list). Moving from the ghost
+/// wraps around to the other end.
+pub struct CursorMut<'a, T> {
+    list: &'a mut LinkedList<T>,
+    // `None` when we are on the ghost.
+    current: Link<T>,
+    // Position of `current`; equal to the list's size on the ghost.
+    index: usize,
+}
+
+// Safety (for every method below): `current` is always `None` or a node of
+// `list`, because every edit made through the cursor keeps it that way.
+impl<'a, T> CursorMut<'a, T> {
+    /// Returns the position of the cursor, or `None` on the ghost.
+    pub fn index(&self) -> Option<usize> {
+        self.current.map(|_| self.index)
+    }
+
+    pub fn current(&mut self) -> Option<&mut T> {
+        self.current
+            .map(|node| unsafe { &mut (*node.as_ptr()).value })
+    }
+
+    /// Returns the element after the current one. On the ghost, that is the
+    /// first element of the list.
+    pub fn peek_next(&mut self) -> Option<&mut T> {
+        let next = match self.current {
+            Some(node) => unsafe { (*node.as_ptr()).next },
+            None => self.list.head,
+        };
+        next.map(|node| unsafe { &mut (*node.as_ptr()).value })
+    }
+
+    /// Returns the element before the current one. On the ghost, that is the
+    /// last element of the list.
+    pub fn peek_prev(&mut self) -> Option<&mut T> {
+        let prev = match self.current {
+            Some(node) => unsafe { (*node.as_ptr()).prev },
+            None => self.list.tail,
+        };
+        prev.map(|node| unsafe { &mut (*node.as_ptr()).value })
+    }
+
+    pub fn move_next(&mut self) {
+        match self.current {
+            Some(node) => {
+                self.current = unsafe { (*node.as_ptr()).next };
+                self.index += 1;
+            }
+            None => {
+                self.current = self.list.head;
+                self.index = 0;
+            }
+        }
+    }
+
+    pub fn move_prev(&mut self) {
+        match self.current {
+            Some(node) => {
+                self.current = unsafe { (*node.as_ptr()).prev };
+                self.index = match self.current {
+                    Some(_) => self.index - 1,
+                    None => self.list.size,
+                };
+            }
+            None => {
+                self.current = self.list.tail;
+                self.index = self.list.size.saturating_sub(1);
             }
         }
-        let mut new_list: LinkedList<T> = LinkedList::new();
-        for val in elements {
-            new_list.push_front(val);
+    }
+
+    /// Inserts `value` before the current element, leaving the cursor on the
+    /// same element. On the ghost, this appends to the end of the list.
+    pub fn insert_before(&mut self, value: T) {
+        let node = self.list.alloc_node(value);
+        unsafe { self.list.link_before(node, self.current) }
+        self.index += 1;
+    }
+
+    /// Inserts `value` after the current element, leaving the cursor where it
+    /// is. On the ghost, this pushes onto the front of the list.
+    pub fn insert_after(&mut self, value: T) {
+        let next = match self.current {
+            Some(node) => unsafe { (*node.as_ptr()).next },
+            None => {
+                self.index += 1;
+                self.list.head
+            }
+        };
+        let node = self.list.alloc_node(value);
+        unsafe { self.list.link_before(node, next) }
+    }
+
+    /// Removes the current element and returns it, moving the cursor onto the
+    /// element that followed it. Does nothing on the ghost.
+    pub fn remove_current(&mut self) -> Option<T> {
+        let node = self.current?;
+        unsafe {
+            self.current = (*node.as_ptr()).next;
+            Some(self.list.unlink(node))
         }
-        new_list
     }
 }
 
-impl ComputeNorm for LinkedList<f64> {
-    fn compute_norm(&self) -> f64 {
-        let mut norm = 0.0;
-        let mut current = &self.head;
-        loop {
-            match current {
-                Some(node) => {
-                    norm += node.value * node.value;
-                    current = &node.next;
-                },
-                None => break,
-            }
-        }
-        norm.sqrt()
+pub struct ExtractIf<'a, T, F> {
+    cursor: CursorMut<'a, T>,
+    predicate: F,
+}
+
+impl<'a, T, F> Iterator for ExtractIf<'a, T, F>
+where
+    F: FnMut(&mut T) -> bool,
+{
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        while let Some(value) = self.cursor.current() {
+            if (self.predicate)(value) {
+                return self.cursor.remove_current();
+            }
+            self.cursor.move_next();
+        }
+        None
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        // Any of the elements from the cursor onwards might match.
+        (0, Some(self.cursor.list.size - self.cursor.index))
+    }
+}
+
+// The iterators walk inwards from both ends and stop once `len` elements have
+// been yielded, so the two ends never hand out the same element.
+pub struct Iter<'a, T> {
+    head: Link<T>,
+    tail: Link<T>,
+    len: usize,
+    marker: PhantomData<&'a Node<T>>,
+}
+
+// Safety (for Iter and IterMut): the list is borrowed for 'a, so its nodes
+// stay alive and aren't relinked while we hand out references into them.
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<&'a T> {
+        if self.len == 0 {
+            return None;
+        }
+        let node = self.head?;
+        self.len -= 1;
+        unsafe {
+            self.head = (*node.as_ptr()).next;
+            Some(&(*node.as_ptr()).value)
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl<'a, T> DoubleEndedIterator for Iter<'a, T> {
+    fn next_back(&mut self) -> Option<&'a T> {
+        if self.len == 0 {
+            return None;
+        }
+        let node = self.tail?;
+        self.len -= 1;
+        unsafe {
+            self.tail = (*node.as_ptr()).prev;
+            Some(&(*node.as_ptr()).value)
+        }
+    }
+}
+
+impl<'a, T> ExactSizeIterator for Iter<'a, T> {}
+
+// Iter only ever reads through its pointers, just like `&'a LinkedList<T>`.
+unsafe impl<'a, T: Sync> Send for Iter<'a, T> {}
+unsafe impl<'a, T: Sync> Sync for Iter<'a, T> {}
+
+pub struct IterMut<'a, T> {
+    head: Link<T>,
+    tail: Link<T>,
+    len: usize,
+    marker: PhantomData<&'a mut Node<T>>,
+}
+
+impl<'a, T> Iterator for IterMut<'a, T> {
+    type Item = &'a mut T;
+
+    fn next(&mut self) -> Option<&'a mut T> {
+        if self.len == 0 {
+            return None;
+        }
+        let node = self.head?;
+        self.len -= 1;
+        unsafe {
+            self.head = (*node.as_ptr()).next;
+            Some(&mut (*node.as_ptr()).value)
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl<'a, T> DoubleEndedIterator for IterMut<'a, T> {
+    fn next_back(&mut self) -> Option<&'a mut T> {
+        if self.len == 0 {
+            return None;
+        }
+        let node = self.tail?;
+        self.len -= 1;
+        unsafe {
+            self.tail = (*node.as_ptr()).prev;
+            Some(&mut (*node.as_ptr()).value)
+        }
+    }
+}
+
+impl<'a, T> ExactSizeIterator for IterMut<'a, T> {}
+
+// IterMut behaves like `&'a mut LinkedList<T>`.
+unsafe impl<'a, T: Send> Send for IterMut<'a, T> {}
+unsafe impl<'a, T: Sync> Sync for IterMut<'a, T> {}
+
+/// Owning iterator returned by `LinkedList::into_iter`.
+pub struct IntoIter<T> {
+    list: LinkedList<T>,
+}
+
+impl<T> Iterator for IntoIter<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        self.list.pop_front()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.list.size, Some(self.list.size))
+    }
+}
+
+impl<T> DoubleEndedIterator for IntoIter<T> {
+    fn next_back(&mut self) -> Option<T> {
+        self.list.pop_back()
+    }
+}
+
+impl<T> ExactSizeIterator for IntoIter<T> {}
+
+impl<'a, T> IntoIterator for &'a LinkedList<T> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Iter<'a, T> {
+        self.iter()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a mut LinkedList<T> {
+    type Item = &'a mut T;
+    type IntoIter = IterMut<'a, T>;
+
+    fn into_iter(self) -> IterMut<'a, T> {
+        self.iter_mut()
+    }
+}
+
+impl<T> IntoIterator for LinkedList<T> {
+    type Item = T;
+    type IntoIter = IntoIter<T>;
+
+    fn into_iter(self) -> IntoIter<T> {
+        IntoIter { list: self }
+    }
+}
+
+impl<T> Index<usize> for LinkedList<T> {
+    type Output = T;
+
+    fn index(&self, index: usize) -> &T {
+        let size = self.size;
+        self.get(index).unwrap_or_else(|| {
+            panic!(
+                "index out of bounds: the len is {} but the index is {}",
+                size, index
+            )
+        })
+    }
+}
+
+impl<T> IndexMut<usize> for LinkedList<T> {
+    fn index_mut(&mut self, index: usize) -> &mut T {
+        let size = self.size;
+        self.get_mut(index).unwrap_or_else(|| {
+            panic!(
+                "index out of bounds: the len is {} but the index is {}",
+                size, index
+            )
+        })
+    }
+}
+
+/// Builds a list from the elements in the order they are written, like `vec!`:
+/// `linked_list![1, 2, 3]` has 1 at the front.
+#[macro_export]
+macro_rules! linked_list {
+    ($($value:expr),* $(,)?) => {
+        $crate::LinkedList::from([$($value),*])
+    };
+}
+
+impl<T, const N: usize> From<[T; N]> for LinkedList<T> {
+    fn from(values: [T; N]) -> LinkedList<T> {
+        IntoIterator::into_iter(values).collect()
+    }
+}
+
+impl<T> FromIterator<T> for LinkedList<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> LinkedList<T> {
+        let mut list = LinkedList::new();
+        list.extend(iter);
+        list
+    }
+}
+
+/// Pushes every element of the iterator onto the back, in order.
+impl<T> Extend<T> for LinkedList<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for value in iter {
+            self.push_back(value);
+        }
+    }
+}
+
+impl<'a, T: Copy + 'a> Extend<&'a T> for LinkedList<T> {
+    fn extend<I: IntoIterator<Item = &'a T>>(&mut self, iter: I) {
+        self.extend(iter.into_iter().copied());
+    }
+}
+
+/// `a + b` is the concatenation of the two lists, built with the O(1)
+/// `append`.
+impl<T> Add for LinkedList<T> {
+    type Output = LinkedList<T>;
+
+    fn add(mut self, mut other: LinkedList<T>) -> LinkedList<T> {
+        self.append(&mut other);
+        self
+    }
+}
+
+impl<T> Default for LinkedList<T> {
+    fn default() -> LinkedList<T> {
+        LinkedList::new()
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for LinkedList<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.write_joined(f, " ")
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for LinkedList<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+/// Lists are equal when they hold equal elements in the same order. Which
+/// pool (if any) their nodes come from doesn't matter.
+impl<T: PartialEq> PartialEq for LinkedList<T> {
+    fn eq(&self, other: &LinkedList<T>) -> bool {
+        self.size == other.size && self.iter().eq(other.iter())
+    }
+}
+
+impl<T: Eq> Eq for LinkedList<T> {}
+
+impl<T> Drop for LinkedList<T> {
+    fn drop(&mut self) {
+        self.clear();
+    }
+}
+
+impl<T: Clone> Clone for LinkedList<T> {
+    fn clone(&self) -> LinkedList<T> {
+        let mut new_list: LinkedList<T> = LinkedList {
+            #[cfg(feature = "std")]
+            pool: self.pool.as_ref().map(|cache| PoolCache::new(cache.pool())),
+            ..LinkedList::new()
+        };
+        new_list.extend(self.iter().cloned());
+        new_list
+    }
+}
+
+// Euclidean norm of a sequence of numbers.
+#[cfg(feature = "std")]
+fn euclidean_norm<I: Iterator<Item = f64>>(values: I) -> f64 {
+    values.map(|x| x * x).sum::<f64>().sqrt()
+}
+
+#[cfg(feature = "std")]
+impl<T: Into<f64> + Copy> ComputeNorm for LinkedList<T> {
+    fn compute_norm(&self) -> f64 {
+        euclidean_norm(self.iter().map(|&x| x.into()))
+    }
+}
+
+#[cfg(feature = "std")]
+impl ComputeNorm for [f64] {
+    fn compute_norm(&self) -> f64 {
+        euclidean_norm(self.iter().copied())
+    }
+}
+
+#[cfg(feature = "std")]
+impl ComputeNorm for Vec<f64> {
+    fn compute_norm(&self) -> f64 {
+        self.as_slice().compute_norm()
+    }
+}
+
+// Lists are (de)serialized as plain sequences, front to back.
+#[cfg(feature = "serde")]
+mod serde_impl {
+    use super::LinkedList;
+    use core::fmt;
+    use core::marker::PhantomData;
+    use serde::de::{Deserialize, Deserializer, SeqAccess, Visitor};
+    use serde::ser::{Serialize, Serializer};
+
+    impl<T: Serialize> Serialize for LinkedList<T> {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            serializer.collect_seq(self.iter())
+        }
+    }
+
+    struct ListVisitor<T> {
+        marker: PhantomData<T>,
+    }
+
+    impl<'de, T: Deserialize<'de>> Visitor<'de> for ListVisitor<T> {
+        type Value = LinkedList<T>;
+
+        fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.write_str("a sequence")
+        }
+
+        fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<LinkedList<T>, A::Error> {
+            let mut list = LinkedList::new();
+            while let Some(value) = seq.next_element()? {
+                list.push_back(value);
+            }
+            Ok(list)
+        }
+    }
+
+    impl<'de, T: Deserialize<'de>> Deserialize<'de> for LinkedList<T> {
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<LinkedList<T>, D::Error> {
+            deserializer.deserialize_seq(ListVisitor {
+                marker: PhantomData,
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use common::test_utils::DropCounter;
+    use std::cell::Cell;
+    use std::rc::Rc;
+
+    fn counted_list(drops: &Cell<usize>, len: usize) -> LinkedList<(usize, DropCounter<'_>)> {
+        let mut list = LinkedList::new();
+        for i in 0..len {
+            list.push_back((i, DropCounter(drops)));
+        }
+        list
+    }
+
+    #[test]
+    fn test_drop_count_whole_list() {
+        let drops = Cell::new(0);
+        let list = counted_list(&drops, 10);
+        drop(list);
+        assert_eq!(drops.get(), 10);
+    }
+
+    #[test]
+    fn test_drop_count_removals() {
+        let drops = Cell::new(0);
+        let mut list = counted_list(&drops, 20);
+        drop(list.pop_front());
+        drop(list.remove(5));
+        assert_eq!(drops.get(), 2);
+        list.retain(|(i, _)| i % 3 != 0);
+        let removed_by_retain = drops.get() - 2;
+        assert_eq!(list.get_size(), 18 - removed_by_retain);
+        list.extract_if(|(i, _)| *i > 15).for_each(drop);
+        let mut cursor = list.cursor_front_mut();
+        cursor.move_next();
+        drop(cursor.remove_current());
+        list.dedup_by_key(|(i, _)| i / 4);
+        let remaining = list.get_size();
+        drop(list);
+        assert_eq!(drops.get(), 20);
+        assert!(remaining > 0);
+    }
+
+    #[test]
+    fn test_drop_count_relinking() {
+        let drops = Cell::new(0);
+        let mut list = counted_list(&drops, 12);
+        let mut back = list.split_off(5);
+        back.reverse();
+        list.append(&mut back);
+        list.rotate_left(3);
+        list.sort_by(|a, b| b.0.cmp(&a.0));
+        list.swap(0, 11);
+        let (mut list, mut even) = list.partition(|v| v.0 % 2 == 1);
+        list.append(&mut even);
+        assert_eq!(drops.get(), 0);
+        let other = counted_list(&drops, 4);
+        let zipped = list.zip(other);
+        // The 8 unpaired elements from `list` are dropped by `zip`.
+        assert_eq!(drops.get(), 8);
+        drop(zipped);
+        assert_eq!(drops.get(), 16);
+    }
+
+    #[test]
+    fn test_drop_count_truncate() {
+        let drops = Cell::new(0);
+        let mut list = counted_list(&drops, 10);
+        list.truncate(7);
+        assert_eq!(drops.get(), 3);
+        list.truncate(7);
+        assert_eq!(drops.get(), 3);
+        drop(list);
+        assert_eq!(drops.get(), 10);
+    }
+
+    #[test]
+    fn test_drop_count_clear() {
+        let drops = Cell::new(0);
+        let mut list = counted_list(&drops, 5);
+        list.clear();
+        assert_eq!(drops.get(), 5);
+        assert!(list.is_empty());
+        assert_eq!(list.front().map(|v| v.0), None);
+        list.debug_validate();
+        list.push_back((0, DropCounter(&drops)));
+        assert_eq!(list.get_size(), 1);
+        drop(list);
+        assert_eq!(drops.get(), 6);
+    }
+
+    #[test]
+    fn test_drop_count_shared_values() {
+        let value = Rc::new(());
+        let mut list = LinkedList::new();
+        for _ in 0..5 {
+            list.push_front(Rc::clone(&value));
+        }
+        let copy = list.clone();
+        assert_eq!(Rc::strong_count(&value), 11);
+        drop(list);
+        drop(copy);
+        assert_eq!(Rc::strong_count(&value), 1);
+    }
+
+    #[test]
+    fn test_validate_after_edits() {
+        let mut list = LinkedList::new();
+        list.debug_validate();
+        for i in 0..10 {
+            list.push_back(i);
+        }
+        list.debug_validate();
+        let mut back = list.split_off(4);
+        back.reverse();
+        list.debug_validate();
+        back.debug_validate();
+        list.append(&mut back);
+        list.sort();
+        list.retain(|&x| x != 3);
+        list.rotate_right(2);
+        list.debug_validate();
+        back.debug_validate();
+        assert!(!list.is_cyclic());
+    }
+
+    #[test]
+    fn test_detects_corruption() {
+        let mut list = LinkedList::new();
+        for i in 0..5 {
+            list.push_back(i);
+        }
+        // Point the tail back at the second node to make a cycle.
+        let second = list.node_at(1);
+        let tail = list.tail.unwrap();
+        unsafe { (*tail.as_ptr()).next = second };
+        assert!(list.is_cyclic());
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            list.debug_validate();
+        }));
+        assert!(result.is_err());
+        // Repair the list so it can be dropped normally.
+        unsafe { (*tail.as_ptr()).next = None };
+        list.debug_validate();
+
+        list.size = 7;
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            list.debug_validate();
+        }));
+        assert!(result.is_err());
+        list.size = 5;
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_pool_recycles_nodes() {
+        let pool = Pool::with_capacity(2);
+        assert_eq!(pool.available(), 2);
+        let mut list = LinkedList::with_pool(&pool);
+        for i in 0..5 {
+            list.push_back(i);
+        }
+        assert_eq!(pool.available(), 0);
+        assert_eq!(list.pop_front(), Some(0));
+        assert_eq!(list.remove(1), Some(2));
+        list.push_front(10);
+
+        // Split-off halves and clones share the pool, and nodes may move
+        // between pooled and unpooled lists.
+        let mut back = list.split_off(2);
+        let mut plain = LinkedList::new();
+        plain.push_back(20);
+        back.append(&mut plain);
+        assert_eq!(back.join(" "), "3 4 20");
+        let copy = back.clone();
+        drop(back);
+        drop(copy);
+        assert_eq!(list.join(" "), "10 1");
+        list.debug_validate();
+        // Every node created so far ends up in the pool: the 2 it started
+        // with, 3 more for the first pushes (push_front reused a freed one),
+        // 3 for the clone and the one that came over from `plain`.
+        drop(list);
+        assert_eq!(pool.available(), 9);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_pool_shared_in_batches() {
+        let pool = Pool::new();
+        let mut producer = LinkedList::with_pool(&pool);
+        for i in 0..200 {
+            producer.push_back(i);
+        }
+        while producer.pop_front().is_some() {}
+        // Most freed nodes are handed back to the shared pool right away so
+        // other lists can use them.
+        assert!(pool.available() >= 100);
+        let mut consumer = LinkedList::with_pool(&pool);
+        for i in 0..100 {
+            consumer.push_back(i);
+        }
+        drop(producer);
+        drop(consumer);
+        assert_eq!(pool.available(), 200);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_pool_drops_values() {
+        let drops = Cell::new(0);
+        let pool = Pool::new();
+        {
+            let mut list = LinkedList::with_pool(&pool);
+            for i in 0..4 {
+                list.push_back((i, DropCounter(&drops)));
+            }
+            drop(list.pop_front());
+            assert_eq!(drops.get(), 1);
+        }
+        assert_eq!(drops.get(), 4);
+        assert_eq!(pool.available(), 4);
+        drop(pool);
+        assert_eq!(drops.get(), 4);
+    }
+
+    #[test]
+    fn test_default_is_empty() {
+        let list: LinkedList<u32> = Default::default();
+        assert!(list.is_empty());
+        assert_eq!(list.get_size(), 0);
+    }
+
+    #[test]
+    fn test_mem_take() {
+        let mut list = LinkedList::new();
+        list.push_front(1);
+        list.push_front(2);
+        let taken = std::mem::take(&mut list);
+        assert!(list.is_empty());
+        assert_eq!(taken.get_size(), 2);
+    }
+
+    #[test]
+    fn test_insert_remove() {
+        let mut list = LinkedList::new();
+        list.insert(0, 3);
+        list.insert(0, 1);
+        list.insert(1, 2);
+        list.insert(3, 4);
+        assert_eq!(list.get_size(), 4);
+        assert_eq!(list.remove(4), None);
+        assert_eq!(list.remove(1), Some(2));
+        assert_eq!(list.remove(2), Some(4));
+        assert_eq!(list.remove(0), Some(1));
+        assert_eq!(list.remove(0), Some(3));
+        assert!(list.is_empty());
+    }
+
+    #[test]
+    fn test_get() {
+        let mut list = LinkedList::new();
+        assert_eq!(list.front(), None);
+        assert_eq!(list.get(0), None);
+        for i in (0..5).rev() {
+            list.push_front(i);
+        }
+        assert_eq!(list.front(), Some(&0));
+        assert_eq!(list.get(3), Some(&3));
+        assert_eq!(list.get(5), None);
+        *list.get_mut(2).unwrap() = 20;
+        *list.front_mut().unwrap() = 10;
+        assert_eq!(list.get(2), Some(&20));
+        assert_eq!(list.get(0), Some(&10));
+        assert_eq!(list.get_mut(7), None);
+    }
+
+    #[test]
+    fn test_contains() {
+        let mut list = LinkedList::new();
+        assert!(!list.contains(&1));
+        list.push_front(1);
+        list.push_front(2);
+        assert!(list.contains(&1));
+        assert!(list.contains(&2));
+        assert!(!list.contains(&3));
+    }
+
+    #[test]
+    fn test_find_position() {
+        let mut list = LinkedList::new();
+        for i in (1..=6).rev() {
+            list.push_front(i);
+        }
+        assert_eq!(
+            list.iter().copied().collect::<Vec<_>>(),
+            vec![1, 2, 3, 4, 5, 6]
+        );
+        assert_eq!(list.find(|&x| x % 4 == 0), Some(&4));
+        assert_eq!(list.find(|&x| x > 6), None);
+        assert_eq!(list.position(|&x| x % 3 == 0), Some(2));
+        assert_eq!(list.position(|&x| x == 0), None);
+    }
+
+    #[test]
+    fn test_retain() {
+        let mut list = LinkedList::new();
+        for i in (1..=10).rev() {
+            list.push_front(i);
+        }
+        list.retain(|&x| x % 2 == 0);
+        assert_eq!(list.get_size(), 5);
+        assert_eq!(
+            list.iter().copied().collect::<Vec<_>>(),
+            vec![2, 4, 6, 8, 10]
+        );
+        list.retain(|&x| x > 100);
+        assert!(list.is_empty());
+        assert_eq!(list.front(), None);
+    }
+
+    #[test]
+    fn test_partition() {
+        let mut list = LinkedList::new();
+        for i in 1..=10 {
+            list.push_back(i);
+        }
+        let (even, odd) = list.partition(|&x| x % 2 == 0);
+        assert_eq!(
+            even.iter().copied().collect::<Vec<_>>(),
+            vec![2, 4, 6, 8, 10]
+        );
+        assert_eq!(odd.iter().copied().collect::<Vec<_>>(), vec![1, 3, 5, 7, 9]);
+        even.debug_validate();
+        odd.debug_validate();
+
+        let (none, all) = odd.partition(|&x| x > 100);
+        assert!(none.is_empty());
+        assert_eq!(all.get_size(), 5);
+        all.debug_validate();
+    }
+
+    #[test]
+    fn test_reverse() {
+        let mut list: LinkedList<u32> = LinkedList::new();
+        list.reverse();
+        assert!(list.is_empty());
+        for i in 0..5 {
+            list.push_front(i);
+        }
+        list.reverse();
+        assert_eq!(list.get_size(), 5);
+        assert_eq!(
+            list.iter().copied().collect::<Vec<_>>(),
+            vec![0, 1, 2, 3, 4]
+        );
+    }
+
+    #[test]
+    fn test_sort() {
+        let mut list = linked_list![2, 0, 7, 3, 1, 9, 3, 5];
+        list.sort();
+        assert_eq!(list.get_size(), 8);
+        assert_eq!(
+            list.iter().copied().collect::<Vec<_>>(),
+            vec![0, 1, 2, 3, 3, 5, 7, 9]
+        );
+        list.sort_by(|a, b| b.cmp(a));
+        assert_eq!(
+            list.iter().copied().collect::<Vec<_>>(),
+            vec![9, 7, 5, 3, 3, 2, 1, 0]
+        );
+    }
+
+    #[test]
+    fn test_sort_is_stable() {
+        let mut list = LinkedList::new();
+        for &pair in [(2, 'a'), (1, 'b'), (2, 'c'), (1, 'd')].iter().rev() {
+            list.push_front(pair);
+        }
+        list.sort_by(|a, b| a.0.cmp(&b.0));
+        assert_eq!(
+            list.iter().copied().collect::<Vec<_>>(),
+            vec![(1, 'b'), (1, 'd'), (2, 'a'), (2, 'c')]
+        );
+    }
+
+    #[test]
+    fn test_merge_sorted() {
+        let merged = linked_list![1, 3, 3, 8].merge_sorted(linked_list![0, 3, 4, 9, 10]);
+        assert_eq!(
+            merged.iter().copied().collect::<Vec<_>>(),
+            vec![0, 1, 3, 3, 3, 4, 8, 9, 10]
+        );
+        merged.debug_validate();
+
+        let merged = merged.merge_sorted(LinkedList::new());
+        assert_eq!(merged.get_size(), 9);
+        let merged = LinkedList::new().merge_sorted(merged);
+        assert_eq!(merged.get_size(), 9);
+        merged.debug_validate();
+    }
+
+    #[test]
+    fn test_merge_sorted_by_is_stable() {
+        let a = linked_list![(3, 'a'), (1, 'a')];
+        let b = linked_list![(3, 'b'), (2, 'b'), (1, 'b')];
+        let merged = a.merge_sorted_by(b, |x, y| y.0.cmp(&x.0));
+        assert_eq!(
+            merged.iter().copied().collect::<Vec<_>>(),
+            vec![(3, 'a'), (3, 'b'), (2, 'b'), (1, 'a'), (1, 'b')]
+        );
+        merged.debug_validate();
+    }
+
+    #[test]
+    fn test_append() {
+        let mut a = LinkedList::new();
+        let mut b = LinkedList::new();
+        a.append(&mut b);
+        assert!(a.is_empty());
+        b.push_front(2);
+        b.push_front(1);
+        a.append(&mut b);
+        assert!(b.is_empty());
+        assert_eq!(a.get_size(), 2);
+        b.push_front(4);
+        b.push_front(3);
+        a.append(&mut b);
+        a.append(&mut LinkedList::new());
+        assert_eq!(a.get_size(), 4);
+        assert_eq!(a.iter().copied().collect::<Vec<_>>(), vec![1, 2, 3, 4]);
+
+        // The tail has to follow every operation that can move the last node.
+        a.remove(3);
+        a.reverse();
+        a.insert(3, 5);
+        a.sort();
+        a.retain(|&x| x != 5);
+        let mut c = LinkedList::new();
+        c.push_front(6);
+        a.append(&mut c);
+        assert_eq!(a.iter().copied().collect::<Vec<_>>(), vec![1, 2, 3, 6]);
+        while a.pop_front().is_some() {}
+        c.push_front(7);
+        a.append(&mut c);
+        assert_eq!(a.iter().copied().collect::<Vec<_>>(), vec![7]);
+    }
+
+    #[test]
+    fn test_add() {
+        let sum = linked_list![0, 1, 2] + linked_list![3, 4, 5] + linked_list![];
+        assert_eq!(
+            sum.iter().copied().collect::<Vec<_>>(),
+            vec![0, 1, 2, 3, 4, 5]
+        );
+        sum.debug_validate();
+        let sum = LinkedList::new() + sum;
+        assert_eq!(sum.get_size(), 6);
+        sum.debug_validate();
+    }
+
+    #[test]
+    fn test_linked_list_macro() {
+        let list = linked_list![1, 2, 3,];
+        assert_eq!(list.iter().copied().collect::<Vec<_>>(), vec![1, 2, 3]);
+        assert_eq!(list.peek_back(), Some(&3));
+        list.debug_validate();
+        let empty: LinkedList<String> = linked_list![];
+        assert!(empty.is_empty());
+        let strings = LinkedList::from([String::from("a"), String::from("b")]);
+        assert_eq!(strings.join(","), "a,b");
+    }
+
+    #[test]
+    fn test_split_off() {
+        let mut list = LinkedList::new();
+        for i in (0..6).rev() {
+            list.push_front(i);
+        }
+        let mut back = list.split_off(4);
+        assert_eq!(list.get_size(), 4);
+        assert_eq!(back.get_size(), 2);
+        assert_eq!(back.iter().copied().collect::<Vec<_>>(), vec![4, 5]);
+
+        let empty = list.split_off(4);
+        assert!(empty.is_empty());
+        let mut all = list.split_off(0);
+        assert!(list.is_empty());
+        assert_eq!(all.get_size(), 4);
+
+        // Both halves need a valid tail afterwards.
+        all.append(&mut back);
+        assert_eq!(
+            all.iter().copied().collect::<Vec<_>>(),
+            vec![0, 1, 2, 3, 4, 5]
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_split_off_out_of_bounds() {
+        let mut list: LinkedList<u32> = LinkedList::new();
+        list.split_off(1);
+    }
+
+    #[test]
+    fn test_cursor_edits() {
+        let mut list = LinkedList::new();
+        for i in (1..=5).rev() {
+            list.push_front(i);
+        }
+        let mut cursor = list.cursor_front_mut();
+        assert_eq!(cursor.index(), Some(0));
+        cursor.move_next();
+        assert_eq!(cursor.current(), Some(&mut 2));
+        assert_eq!(cursor.peek_next(), Some(&mut 3));
+        cursor.insert_before(10);
+        assert_eq!(cursor.index(), Some(2));
+        cursor.insert_after(20);
+        assert_eq!(cursor.remove_current(), Some(2));
+        assert_eq!(cursor.current(), Some(&mut 20));
+        *cursor.current().unwrap() += 1;
+        cursor.move_next();
+        cursor.move_next();
+        cursor.move_next();
+        assert_eq!(cursor.remove_current(), Some(5));
+        assert_eq!(cursor.index(), None);
+        assert_eq!(cursor.current(), None);
+        assert_eq!(cursor.remove_current(), None);
+        cursor.insert_before(30);
+        cursor.insert_after(0);
+        cursor.move_next();
+        assert_eq!(cursor.index(), Some(0));
+        assert_eq!(
+            list.iter().copied().collect::<Vec<_>>(),
+            vec![0, 1, 10, 21, 3, 4, 30]
+        );
+        assert_eq!(list.get_size(), 7);
+
+        let mut other = LinkedList::new();
+        other.push_front(40);
+        list.append(&mut other);
+        assert_eq!(list.get(7), Some(&40));
+    }
+
+    #[test]
+    fn test_cursor_move_prev() {
+        let mut list = LinkedList::new();
+        for i in (1..=3).rev() {
+            list.push_front(i);
+        }
+        let mut cursor = list.cursor_front_mut();
+        cursor.move_prev();
+        assert_eq!(cursor.index(), None);
+        assert_eq!(cursor.peek_prev(), Some(&mut 3));
+        cursor.move_prev();
+        assert_eq!(cursor.index(), Some(2));
+        assert_eq!(cursor.current(), Some(&mut 3));
+        cursor.move_prev();
+        assert_eq!(cursor.peek_prev(), Some(&mut 1));
+        cursor.insert_before(10);
+        assert_eq!(cursor.index(), Some(2));
+        assert_eq!(cursor.remove_current(), Some(2));
+        cursor.move_prev();
+        cursor.move_prev();
+        assert_eq!(cursor.current(), Some(&mut 1));
+        assert_eq!(list.join(" "), "1 10 3");
+        assert_eq!(list.peek_back(), Some(&3));
+    }
+
+    #[test]
+    fn test_cursor_on_empty_list() {
+        let mut list = LinkedList::new();
+        let mut cursor = list.cursor_front_mut();
+        assert_eq!(cursor.index(), None);
+        assert_eq!(cursor.peek_next(), None);
+        cursor.insert_after(2);
+        assert_eq!(cursor.index(), None);
+        assert_eq!(cursor.peek_next(), Some(&mut 2));
+        cursor.insert_before(3);
+        cursor.move_next();
+        cursor.insert_before(1);
+        assert_eq!(list.iter().copied().collect::<Vec<_>>(), vec![1, 2, 3]);
+        let mut other = LinkedList::new();
+        other.push_front(4);
+        list.append(&mut other);
+        assert_eq!(list.iter().copied().collect::<Vec<_>>(), vec![1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn test_extract_if() {
+        let mut list = LinkedList::new();
+        for i in (1..=8).rev() {
+            list.push_front(i);
+        }
+        let evens: Vec<_> = list.extract_if(|x| *x % 2 == 0).collect();
+        assert_eq!(evens, vec![2, 4, 6, 8]);
+        assert_eq!(list.get_size(), 4);
+        assert_eq!(list.iter().copied().collect::<Vec<_>>(), vec![1, 3, 5, 7]);
+
+        // Stopping early leaves the rest of the list alone.
+        assert_eq!(list.extract_if(|x| *x > 1).next(), Some(3));
+        assert_eq!(list.iter().copied().collect::<Vec<_>>(), vec![1, 5, 7]);
+        list.extract_if(|_| true).for_each(drop);
+        assert!(list.is_empty());
+    }
+
+    #[test]
+    fn test_dedup() {
+        let mut list = LinkedList::new();
+        for &i in [1, 1, 2, 3, 3, 3, 1, 4, 4].iter().rev() {
+            list.push_front(i);
+        }
+        list.dedup();
+        assert_eq!(list.get_size(), 5);
+        assert_eq!(
+            list.iter().copied().collect::<Vec<_>>(),
+            vec![1, 2, 3, 1, 4]
+        );
+        list.dedup_by_key(|x| *x / 2);
+        assert_eq!(list.iter().copied().collect::<Vec<_>>(), vec![1, 2, 1, 4]);
+        let mut other = LinkedList::new();
+        other.push_front(5);
+        list.append(&mut other);
+        assert_eq!(list.get(4), Some(&5));
+    }
+
+    #[test]
+    fn test_double_ended_iter() {
+        let mut list = LinkedList::new();
+        for i in 0..6 {
+            list.push_back(i);
+        }
+        assert_eq!(
+            list.iter().rev().copied().collect::<Vec<_>>(),
+            vec![5, 4, 3, 2, 1, 0]
+        );
+        assert_eq!(list.iter().rfind(|&&x| x % 2 == 0), Some(&4));
+        let mut iter = list.iter();
+        assert_eq!(iter.next(), Some(&0));
+        assert_eq!(iter.next_back(), Some(&5));
+        assert_eq!(iter.next_back(), Some(&4));
+        assert_eq!(iter.next(), Some(&1));
+        assert_eq!(iter.next(), Some(&2));
+        assert_eq!(iter.next_back(), Some(&3));
+        assert_eq!(iter.next(), None);
+        assert_eq!(iter.next_back(), None);
+
+        for (i, value) in list.iter_mut().rev().enumerate() {
+            *value += i * 10;
+        }
+        assert_eq!(
+            list.iter().copied().collect::<Vec<_>>(),
+            vec![50, 41, 32, 23, 14, 5]
+        );
+
+        let mut into_iter = list.into_iter();
+        assert_eq!(into_iter.next_back(), Some(5));
+        assert_eq!(into_iter.next(), Some(50));
+        assert_eq!(into_iter.rev().collect::<Vec<_>>(), vec![14, 23, 32, 41]);
+    }
+
+    #[test]
+    fn test_exact_size() {
+        let mut list = LinkedList::new();
+        for i in 0..5 {
+            list.push_back(i);
+        }
+        let mut iter = list.iter();
+        assert_eq!(iter.len(), 5);
+        iter.next();
+        iter.next_back();
+        assert_eq!(iter.size_hint(), (3, Some(3)));
+        iter.by_ref().count();
+        assert_eq!(iter.len(), 0);
+
+        let mut iter_mut = list.iter_mut();
+        iter_mut.next_back();
+        assert_eq!(iter_mut.len(), 4);
+
+        {
+            let mut extract = list.extract_if(|x| *x == 1);
+            assert_eq!(extract.size_hint(), (0, Some(5)));
+            assert_eq!(extract.next(), Some(1));
+            assert_eq!(extract.size_hint(), (0, Some(3)));
+        }
+
+        let mut into_iter = list.into_iter();
+        into_iter.next();
+        assert_eq!(into_iter.len(), 3);
+        into_iter.next_back();
+        assert_eq!(into_iter.size_hint(), (2, Some(2)));
+    }
+
+    #[test]
+    fn test_truncate() {
+        let mut list = LinkedList::new();
+        for i in 0..6 {
+            list.push_back(i);
+        }
+        list.truncate(10);
+        assert_eq!(list.get_size(), 6);
+        list.truncate(4);
+        assert_eq!(list.iter().copied().collect::<Vec<_>>(), vec![0, 1, 2, 3]);
+        assert_eq!(list.peek_back(), Some(&3));
+        list.debug_validate();
+        list.truncate(0);
+        assert!(list.is_empty());
+        list.debug_validate();
+    }
+
+    #[test]
+    fn test_pop_back() {
+        let mut list = LinkedList::new();
+        for i in 0..3 {
+            list.push_front(i);
+        }
+        assert_eq!(list.pop_back(), Some(0));
+        assert_eq!(list.pop_back(), Some(1));
+        assert_eq!(list.peek_back(), Some(&2));
+        assert_eq!(list.pop_back(), Some(2));
+        assert_eq!(list.pop_back(), None);
+        list.debug_validate();
+    }
+
+    #[test]
+    fn test_peek() {
+        let mut list = LinkedList::new();
+        assert_eq!(list.peek_front(), None);
+        assert_eq!(list.peek_back(), None);
+        assert_eq!(list.peek_back_mut(), None);
+        list.push_front(2);
+        list.push_front(1);
+        assert_eq!(list.peek_front(), Some(&1));
+        assert_eq!(list.peek_back(), Some(&2));
+        *list.peek_front_mut().unwrap() = 10;
+        *list.peek_back_mut().unwrap() = 20;
+        assert_eq!(list.get_size(), 2);
+        assert_eq!(list.pop_front(), Some(10));
+        assert_eq!(list.peek_back(), Some(&20));
+        assert_eq!(list.pop_front(), Some(20));
+        assert_eq!(list.peek_back(), None);
+    }
+
+    #[test]
+    fn test_index() {
+        let mut list = LinkedList::new();
+        for i in (0..4).rev() {
+            list.push_front(i);
+        }
+        assert_eq!(list[0], 0);
+        assert_eq!(list[3], 3);
+        list[2] *= 10;
+        assert_eq!(list[2], 20);
+    }
+
+    #[test]
+    #[should_panic(expected = "index out of bounds")]
+    fn test_index_out_of_bounds() {
+        let mut list = LinkedList::new();
+        list.push_front(1);
+        let _ = list[1];
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde_round_trip() {
+        let mut list = LinkedList::new();
+        for i in (1..=3).rev() {
+            list.push_front(i);
+        }
+        let json = serde_json::to_string(&list).unwrap();
+        assert_eq!(json, "[1,2,3]");
+        let mut decoded: LinkedList<u32> = serde_json::from_str(&json).unwrap();
+        assert_eq!(decoded.get_size(), 3);
+        assert_eq!(decoded.iter().copied().collect::<Vec<_>>(), vec![1, 2, 3]);
+        assert_eq!(decoded.peek_back(), Some(&3));
+        decoded.insert(3, 4);
+        assert_eq!(decoded.peek_back(), Some(&4));
+        assert!(serde_json::from_str::<LinkedList<u32>>("{}").is_err());
+    }
+
+    #[test]
+    fn test_display_and_join() {
+        let mut list = LinkedList::new();
+        assert_eq!(list.to_string(), "");
+        assert_eq!(list.join(", "), "");
+        list.push_front(3);
+        assert_eq!(list.to_string(), "3");
+        list.push_front(2);
+        list.push_front(1);
+        assert_eq!(list.to_string(), "1 2 3");
+        assert_eq!(list.join(", "), "1, 2, 3");
+        assert_eq!(list.join(""), "123");
+    }
+
+    #[test]
+    fn test_from_iter_and_extend() {
+        let mut list: LinkedList<i32> = (1..4).collect();
+        assert_eq!(list.join(" "), "1 2 3");
+        list.extend(vec![4, 5]);
+        list.extend(&[6, 7]);
+        assert_eq!(list.join(" "), "1 2 3 4 5 6 7");
+        assert_eq!(list.get_size(), 7);
+        assert_eq!(list.peek_back(), Some(&7));
+        list.debug_validate();
+
+        let empty: LinkedList<String> = core::iter::empty().collect();
+        assert!(empty.is_empty());
+    }
+
+    #[test]
+    fn test_eq_and_debug() {
+        let list = linked_list![1, 2, 3];
+        assert_eq!(list, (1..=3).collect::<LinkedList<_>>());
+        assert_ne!(list, linked_list![1, 2]);
+        assert_ne!(list, linked_list![1, 2, 4]);
+        assert_eq!(LinkedList::<u8>::new(), LinkedList::new());
+        assert_eq!(format!("{:?}", list), "[1, 2, 3]");
+        assert_eq!(format!("{:?}", linked_list!["a"]), "[\"a\"]");
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_eq_ignores_pool() {
+        let pool = Pool::new();
+        let mut pooled = LinkedList::with_pool(&pool);
+        pooled.extend(&[1, 2, 3]);
+        assert_eq!(pooled, linked_list![1, 2, 3]);
+    }
+
+    #[test]
+    fn test_clone() {
+        let mut list = LinkedList::new();
+        let empty = list.clone();
+        assert!(empty.is_empty());
+        for i in (0..5).rev() {
+            list.push_front(i.to_string());
+        }
+        let mut copy = list.clone();
+        assert_eq!(copy.get_size(), 5);
+        assert_eq!(copy.join(" "), "0 1 2 3 4");
+        copy.push_back("5".to_string());
+        assert_eq!(copy.peek_back().unwrap(), "5");
+        assert_eq!(list.get_size(), 5);
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn test_clone_large_list() {
+        // The old clone built a Vec with `insert(0, ..)`, which made this take
+        // billions of element moves. A single forward pass is instant.
+        let mut list = LinkedList::new();
+        for i in 0..200_000 {
+            list.push_front(i);
+        }
+        let copy = list.clone();
+        assert_eq!(copy.get_size(), 200_000);
+        assert!(copy.iter().eq(list.iter()));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_compute_norm() {
+        let mut floats: LinkedList<f64> = LinkedList::new();
+        assert_eq!(floats.compute_norm(), 0.0);
+        floats.push_front(3.0);
+        floats.push_front(4.0);
+        assert_eq!(floats.compute_norm(), 5.0);
+
+        let mut ints: LinkedList<i32> = LinkedList::new();
+        ints.push_front(-12);
+        ints.push_front(5);
+        assert_eq!(ints.compute_norm(), 13.0);
+
+        let mut small: LinkedList<f32> = LinkedList::new();
+        small.push_front(0.5);
+        assert_eq!(small.compute_norm(), 0.5);
+
+        let v = vec![6.0, 8.0];
+        assert_eq!(v.compute_norm(), 10.0);
+        assert_eq!(v[..1].compute_norm(), 6.0);
+    }
+
+    #[test]
+    fn test_swap() {
+        let mut list = LinkedList::new();
+        for i in (0..5).rev() {
+            list.push_front(i);
+        }
+        list.swap(1, 3);
+        list.swap(4, 0);
+        list.swap(2, 2);
+        assert_eq!(list.join(" "), "4 3 2 1 0");
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_swap_out_of_bounds() {
+        let mut list = LinkedList::new();
+        list.push_front(1);
+        list.swap(0, 1);
+    }
+
+    #[test]
+    fn test_rotate() {
+        let mut list = LinkedList::new();
+        for i in (0..5).rev() {
+            list.push_front(i);
+        }
+        list.rotate_left(2);
+        assert_eq!(list.join(" "), "2 3 4 0 1");
+        assert_eq!(list.peek_back(), Some(&1));
+        list.rotate_right(3);
+        assert_eq!(list.join(" "), "4 0 1 2 3");
+        list.rotate_left(5);
+        list.rotate_right(0);
+        assert_eq!(list.join(" "), "4 0 1 2 3");
+        assert_eq!(list.get_size(), 5);
+        list.push_back(5);
+        assert_eq!(list.join(" "), "4 0 1 2 3 5");
+    }
+
+    #[test]
+    fn test_nth_from_end() {
+        let mut list = LinkedList::new();
+        assert_eq!(list.nth_from_end(0), None);
+        for i in (0..5).rev() {
+            list.push_front(i);
+        }
+        assert_eq!(list.nth_from_end(0), Some(&4));
+        assert_eq!(list.nth_from_end(1), Some(&3));
+        assert_eq!(list.nth_from_end(4), Some(&0));
+        assert_eq!(list.nth_from_end(5), None);
+        *list.nth_from_end_mut(2).unwrap() = 20;
+        assert_eq!(list.nth_from_end(2), Some(&20));
+        assert_eq!(list.nth_from_end_mut(5), None);
+        assert_eq!(list.nth_from_end_mut(usize::MAX), None);
+    }
+
+    #[test]
+    fn test_map_filter() {
+        let mut list = LinkedList::new();
+        for i in (1..=5).rev() {
+            list.push_front(i);
+        }
+        let strings = list.map(|x| format!("<{}>", x));
+        assert_eq!(strings.join(""), "<1><2><3><4><5>");
+        assert_eq!(strings.peek_back().unwrap(), "<5>");
+        let odds = list.filter(|&x| x % 2 == 1);
+        assert_eq!(odds.join(" "), "1 3 5");
+        assert_eq!(odds.get_size(), 3);
+        assert_eq!(list.get_size(), 5);
+        assert!(list.filter(|_| false).is_empty());
+    }
+
+    #[test]
+    fn test_zip() {
+        let mut a = LinkedList::new();
+        let mut b = LinkedList::new();
+        for i in (1..=4).rev() {
+            a.push_front(i as f64);
+        }
+        for i in (1..=3).rev() {
+            b.push_front(i as f64 * 10.0);
+        }
+        let pairs = a.zip(b);
+        assert_eq!(pairs.get_size(), 3);
+        assert_eq!(pairs.peek_back(), Some(&(3.0, 30.0)));
+        let dot: f64 = pairs.iter().map(|(x, y)| x * y).sum();
+        assert_eq!(dot, 140.0);
+        assert!(LinkedList::<u8>::new().zip(pairs).is_empty());
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_insert_out_of_bounds() {
+        let mut list = LinkedList::new();
+        list.push_front(1);
+        list.insert(2, 2);
     }
 }
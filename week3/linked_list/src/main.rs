@@ -1,5 +1,4 @@
 use linked_list::LinkedList;
-pub mod linked_list;
 
 fn main() {
     let mut list: LinkedList<u32> = LinkedList::new();
@@ -13,10 +12,10 @@ fn main() {
     println!("top element: {}", list.pop_front().unwrap());
     println!("{}", list);
     println!("size: {}", list.get_size());
-    println!("{}", list.to_string()); // ToString impl for anything impl Display
+    let as_string = list.to_string(); // ToString impl for anything impl Display
+    println!("{}", as_string);
 
-    // If you implement iterator trait:
-    //for val in &list {
-    //    println!("{}", val);
-    //}
+    for val in &list {
+        println!("{}", val);
+    }
 }
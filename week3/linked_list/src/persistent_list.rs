@@ -0,0 +1,170 @@
+use alloc::rc::Rc;
+
+/// An immutable singly linked list whose versions share structure.
+///
+/// `push_front` and `pop_front` leave `self` untouched and return a new list
+/// that shares its tail with the old one, so keeping old versions around (for
+/// undo or backtracking) costs one node per push. Cloning is O(1).
+pub struct PersistentList<T> {
+    head: Link<T>,
+    size: usize,
+}
+
+type Link<T> = Option<Rc<Node<T>>>;
+
+struct Node<T> {
+    value: T,
+    next: Link<T>,
+}
+
+impl<T> PersistentList<T> {
+    pub fn new() -> PersistentList<T> {
+        PersistentList {
+            head: None,
+            size: 0,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.size
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.size == 0
+    }
+
+    /// Returns a new list with `value` in front of the elements of `self`.
+    pub fn push_front(&self, value: T) -> PersistentList<T> {
+        PersistentList {
+            head: Some(Rc::new(Node {
+                value,
+                next: self.head.clone(),
+            })),
+            size: self.size + 1,
+        }
+    }
+
+    /// Returns the list without its first element (or another empty list if
+    /// `self` is empty). Use `front` to look at the element being dropped.
+    pub fn pop_front(&self) -> PersistentList<T> {
+        match &self.head {
+            Some(node) => PersistentList {
+                head: node.next.clone(),
+                size: self.size - 1,
+            },
+            None => PersistentList::new(),
+        }
+    }
+
+    pub fn front(&self) -> Option<&T> {
+        self.head.as_ref().map(|node| &node.value)
+    }
+
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter {
+            next: self.head.as_deref(),
+        }
+    }
+}
+
+impl<T> Clone for PersistentList<T> {
+    fn clone(&self) -> PersistentList<T> {
+        PersistentList {
+            head: self.head.clone(),
+            size: self.size,
+        }
+    }
+}
+
+impl<T> Default for PersistentList<T> {
+    fn default() -> PersistentList<T> {
+        PersistentList::new()
+    }
+}
+
+impl<T> Drop for PersistentList<T> {
+    fn drop(&mut self) {
+        // Free nodes iteratively for as long as we hold the only reference,
+        // and stop at the first node another list still shares.
+        let mut current = self.head.take();
+        while let Some(node) = current {
+            match Rc::try_unwrap(node) {
+                Ok(mut node) => current = node.next.take(),
+                Err(_) => break,
+            }
+        }
+    }
+}
+
+pub struct Iter<'a, T> {
+    next: Option<&'a Node<T>>,
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<&'a T> {
+        let node = self.next?;
+        self.next = node.next.as_deref();
+        Some(&node.value)
+    }
+}
+
+impl<'a, T> IntoIterator for &'a PersistentList<T> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Iter<'a, T> {
+        self.iter()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_versions_share_tails() {
+        let empty = PersistentList::new();
+        let one = empty.push_front(1);
+        let two = one.push_front(2);
+        let other = one.push_front(3);
+        assert!(empty.is_empty());
+        assert_eq!(two.iter().copied().collect::<Vec<_>>(), vec![2, 1]);
+        assert_eq!(other.iter().copied().collect::<Vec<_>>(), vec![3, 1]);
+        assert_eq!(two.len(), 2);
+
+        let popped = two.pop_front();
+        assert_eq!(popped.front(), Some(&1));
+        assert_eq!(popped.len(), 1);
+        assert_eq!(two.front(), Some(&2));
+        assert!(empty.pop_front().is_empty());
+    }
+
+    #[test]
+    fn test_drop_keeps_shared_nodes() {
+        let value = Rc::new(());
+        let base = PersistentList::new()
+            .push_front(Rc::clone(&value))
+            .push_front(Rc::clone(&value));
+        let longer = base.push_front(Rc::clone(&value));
+        assert_eq!(Rc::strong_count(&value), 4);
+        drop(longer);
+        assert_eq!(Rc::strong_count(&value), 3);
+        let copy = base.clone();
+        drop(base);
+        assert_eq!(copy.len(), 2);
+        drop(copy);
+        assert_eq!(Rc::strong_count(&value), 1);
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn test_long_list_drop() {
+        let mut list = PersistentList::new();
+        for i in 0..100_000 {
+            list = list.push_front(i);
+        }
+        assert_eq!(list.len(), 100_000);
+    }
+}
@@ -0,0 +1,126 @@
+use crate::linked_list::Node;
+use std::mem::MaybeUninit;
+use std::ptr::NonNull;
+use std::sync::{Arc, Mutex, MutexGuard};
+
+// How many nodes a list moves between its local cache and the shared pool at
+// a time, so the pool's lock is taken once per batch rather than per node.
+const BATCH: usize = 32;
+
+/// A stash of spare list nodes.
+///
+/// Lists created with `LinkedList::with_pool` take nodes from here before
+/// asking the global allocator, and return them here instead of freeing them,
+/// so push/pop-heavy workloads stop churning the allocator once the pool has
+/// warmed up. The pool is a cheap handle: clones (and all lists using it)
+/// share the same nodes, and they are only freed once every handle is gone.
+pub struct Pool<T> {
+    free: Arc<Mutex<FreeNodes<T>>>,
+}
+
+// Allocations sized for a `Node<T>` whose contents are uninitialized.
+struct FreeNodes<T>(Vec<NonNull<Node<T>>>);
+
+// The free nodes hold no `T` values, only raw memory, so they can move
+// between threads whatever `T` is.
+unsafe impl<T> Send for FreeNodes<T> {}
+
+impl<T> Pool<T> {
+    pub fn new() -> Pool<T> {
+        Pool::with_capacity(0)
+    }
+
+    /// Creates a pool that already holds `capacity` spare nodes.
+    pub fn with_capacity(capacity: usize) -> Pool<T> {
+        let nodes = (0..capacity)
+            .map(|_| NonNull::from(Box::leak(Box::new(MaybeUninit::<Node<T>>::uninit()))).cast())
+            .collect();
+        Pool {
+            free: Arc::new(Mutex::new(FreeNodes(nodes))),
+        }
+    }
+
+    /// Returns the number of spare nodes in the pool. Lists keep a few more
+    /// cached on their side, which come back when the list is dropped.
+    pub fn available(&self) -> usize {
+        self.lock().0.len()
+    }
+
+    fn lock(&self) -> MutexGuard<'_, FreeNodes<T>> {
+        self.free
+            .lock()
+            .expect("another thread panicked while holding the pool lock")
+    }
+}
+
+impl<T> Clone for Pool<T> {
+    fn clone(&self) -> Pool<T> {
+        Pool {
+            free: Arc::clone(&self.free),
+        }
+    }
+}
+
+impl<T> Default for Pool<T> {
+    fn default() -> Pool<T> {
+        Pool::new()
+    }
+}
+
+impl<T> Drop for FreeNodes<T> {
+    fn drop(&mut self) {
+        for node in self.0.drain(..) {
+            // Safety: every pooled node came from a `Box<Node<T>>` (or an
+            // equivalent `MaybeUninit` box) and has no live value, so free it
+            // without running any destructor.
+            unsafe { drop(Box::from_raw(node.cast::<MaybeUninit<Node<T>>>().as_ptr())) }
+        }
+    }
+}
+
+/// A list's own end of a `Pool`, holding a small cache of spare nodes.
+pub(crate) struct PoolCache<T> {
+    pool: Pool<T>,
+    spare: Vec<NonNull<Node<T>>>,
+}
+
+// Like `FreeNodes`, the cache only holds uninitialized memory.
+unsafe impl<T> Send for PoolCache<T> {}
+unsafe impl<T> Sync for PoolCache<T> {}
+
+impl<T> PoolCache<T> {
+    pub(crate) fn new(pool: &Pool<T>) -> PoolCache<T> {
+        PoolCache {
+            pool: pool.clone(),
+            spare: Vec::new(),
+        }
+    }
+
+    pub(crate) fn pool(&self) -> &Pool<T> {
+        &self.pool
+    }
+
+    // Hands out a spare node, whose contents are uninitialized.
+    pub(crate) fn take(&mut self) -> Option<NonNull<Node<T>>> {
+        if self.spare.is_empty() {
+            let mut free = self.pool.lock();
+            let keep = free.0.len().saturating_sub(BATCH);
+            self.spare.extend(free.0.drain(keep..));
+        }
+        self.spare.pop()
+    }
+
+    // Takes back a node whose value has already been moved out.
+    pub(crate) fn give(&mut self, node: NonNull<Node<T>>) {
+        self.spare.push(node);
+        if self.spare.len() >= 2 * BATCH {
+            self.pool.lock().0.extend(self.spare.drain(BATCH..));
+        }
+    }
+}
+
+impl<T> Drop for PoolCache<T> {
+    fn drop(&mut self) {
+        self.pool.lock().0.append(&mut self.spare);
+    }
+}
@@ -0,0 +1,92 @@
+//! Property tests that run random operation sequences against both a
+//! `LinkedList` and a `VecDeque` and check they always agree.
+
+use linked_list::LinkedList;
+use proptest::prelude::*;
+use std::collections::VecDeque;
+
+#[derive(Debug, Clone)]
+enum Op {
+    PushFront(i32),
+    PushBack(i32),
+    PopFront,
+    Insert(usize, i32),
+    Remove(usize),
+    Reverse,
+    // Moves everything from `at` onwards into the second list.
+    SplitOff(usize),
+    // Moves the second list onto the end of the first.
+    Append,
+}
+
+fn op() -> impl Strategy<Value = Op> {
+    prop_oneof![
+        any::<i32>().prop_map(Op::PushFront),
+        any::<i32>().prop_map(Op::PushBack),
+        Just(Op::PopFront),
+        (any::<usize>(), any::<i32>()).prop_map(|(i, v)| Op::Insert(i, v)),
+        any::<usize>().prop_map(Op::Remove),
+        Just(Op::Reverse),
+        any::<usize>().prop_map(Op::SplitOff),
+        Just(Op::Append),
+    ]
+}
+
+fn assert_matches(list: &LinkedList<i32>, model: &VecDeque<i32>) {
+    list.debug_validate();
+    assert_eq!(list.get_size(), model.len());
+    assert_eq!(list.is_empty(), model.is_empty());
+    assert_eq!(list.peek_front(), model.front());
+    assert_eq!(list.peek_back(), model.back());
+    assert!(list.iter().eq(model.iter()));
+}
+
+proptest! {
+    // Far too slow under Miri; the unit tests cover the same code paths.
+    #[cfg_attr(miri, ignore)]
+    #[test]
+    fn list_matches_vecdeque(ops in prop::collection::vec(op(), 0..200)) {
+        let mut list = LinkedList::new();
+        let mut model = VecDeque::new();
+        let mut other = LinkedList::new();
+        let mut other_model = VecDeque::new();
+        for op in ops {
+            match op {
+                Op::PushFront(v) => {
+                    list.push_front(v);
+                    model.push_front(v);
+                }
+                Op::PushBack(v) => {
+                    list.push_back(v);
+                    model.push_back(v);
+                }
+                Op::PopFront => assert_eq!(list.pop_front(), model.pop_front()),
+                Op::Insert(i, v) => {
+                    let i = i % (model.len() + 1);
+                    list.insert(i, v);
+                    model.insert(i, v);
+                }
+                Op::Remove(i) => {
+                    // Deliberately allow out-of-bounds indices here.
+                    let i = i % (model.len() + 2);
+                    assert_eq!(list.remove(i), model.remove(i));
+                }
+                Op::Reverse => {
+                    list.reverse();
+                    model = model.into_iter().rev().collect();
+                }
+                Op::SplitOff(at) => {
+                    let at = at % (model.len() + 1);
+                    other = list.split_off(at);
+                    other_model = model.split_off(at);
+                }
+                Op::Append => {
+                    list.append(&mut other);
+                    model.append(&mut other_model);
+                }
+            }
+            assert_matches(&list, &model);
+            assert_matches(&other, &other_model);
+        }
+    }
+}
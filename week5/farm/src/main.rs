@@ -14,7 +14,7 @@ fn is_prime(num: u32) -> bool {
         return false;
     }
     for factor in 2..((num as f64).sqrt().floor() as u32) {
-        if num % factor == 0 {
+        if num.is_multiple_of(factor) {
             return false;
         }
     }
@@ -37,7 +37,7 @@ fn factor_number(num: u32) {
     let mut factors = Vec::new();
     let mut curr_num = num;
     for factor in 2..num {
-        while curr_num % factor == 0 {
+        while curr_num.is_multiple_of(factor) {
             factors.push(factor);
             curr_num /= factor;
         }
@@ -84,11 +84,8 @@ fn main() {
     for _ in 0..num_threads {
         let queue_ref = queue.clone();
         threads.push(thread::spawn(move || {
-            loop {
-                match get_number(&queue_ref) {
-                    Some(number) => factor_number(number),
-                    None => break
-                };
+            while let Some(number) = get_number(&queue_ref) {
+                factor_number(number);
             }
         }));
     }
@@ -0,0 +1,321 @@
+//! parallel_map itself, separate from the demo so it can be tested on its own, and a
+//! `ThreadPool` for mapping over and over without starting threads each time.
+
+mod pool;
+
+pub use crate::pool::{MapIter, ThreadPool};
+
+use std::any::Any;
+use std::fmt;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread;
+
+/// Applies `f` to every element of `input_vec` on `num_threads` worker threads, and returns the
+/// results in the order of the elements they came from. Zero threads is taken to mean one.
+///
+/// Workers take elements from a shared queue one at a time, so a slow element only holds up the
+/// worker that has it while the others carry on with the rest.
+///
+/// # Panics
+///
+/// If `f` panics. Use `parallel_map_catching` to get the panics back as errors instead.
+pub fn parallel_map<T, U, F>(input_vec: Vec<T>, num_threads: usize, f: F) -> Vec<U>
+where
+    F: Fn(T) -> U + Send + Sync + 'static,
+    T: Send + 'static,
+    U: Send + 'static,
+{
+    map_indexed(input_vec, num_threads, f, |_| false)
+        .into_iter()
+        .map(|res| res.expect("every element is mapped once its worker has finished"))
+        .collect()
+}
+
+/// Like `parallel_map`, but a panic in `f` only fails the element it happened on: its result is
+/// the `Panicked` error, and the worker carries on with the next element.
+pub fn parallel_map_catching<T, U, F>(
+    input_vec: Vec<T>,
+    num_threads: usize,
+    f: F,
+) -> Vec<Result<U, Panicked>>
+where
+    F: Fn(T) -> U + Send + Sync + 'static,
+    T: Send + 'static,
+    U: Send + 'static,
+{
+    // Each call gets an element of its own, so all a panic can leave half-done is whatever state
+    // `f` itself keeps, which is the caller's to know about
+    let catching = move |item| {
+        panic::catch_unwind(AssertUnwindSafe(|| f(item))).map_err(Panicked::from_payload)
+    };
+    map_indexed(input_vec, num_threads, catching, |_| false)
+        .into_iter()
+        .map(|res| res.expect("every element is mapped once its worker has finished"))
+        .collect()
+}
+
+/// Applies the fallible `f` to every element of `input_vec` on `num_threads` worker threads, and
+/// returns the results in order if they are all `Ok`.
+///
+/// Otherwise stops handing out elements once there is an error (elements already being worked on
+/// are finished, but the rest are never mapped), and returns the error of the first element to
+/// fail, in the order of `input_vec`. Elements are handed out in that order, so every element
+/// before a failed one has been mapped too.
+pub fn parallel_try_map<T, U, E, F>(
+    input_vec: Vec<T>,
+    num_threads: usize,
+    f: F,
+) -> Result<Vec<U>, E>
+where
+    F: Fn(T) -> Result<U, E> + Send + Sync + 'static,
+    T: Send + 'static,
+    U: Send + 'static,
+    E: Send + 'static,
+{
+    let results = map_indexed(input_vec, num_threads, f, Result::is_err);
+    let mut output_vec = Vec::with_capacity(results.len());
+    // Only the elements after an error can be left unmapped, and the `?` returns before those
+    for res in results {
+        output_vec.push(res.expect("every element before an error is mapped")?);
+    }
+    Ok(output_vec)
+}
+
+/// A panic caught while mapping an element.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Panicked {
+    /// The panic's message, if it was given one (as `panic!` with a message does).
+    pub message: Option<String>,
+}
+
+impl Panicked {
+    fn from_payload(payload: Box<dyn Any + Send>) -> Panicked {
+        let message = match payload.downcast::<String>() {
+            Ok(message) => Some(*message),
+            Err(payload) => payload.downcast_ref::<&str>().map(|s| s.to_string()),
+        };
+        Panicked { message }
+    }
+}
+
+impl fmt::Display for Panicked {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.message {
+            Some(message) => write!(f, "panicked: {}", message),
+            None => write!(f, "panicked"),
+        }
+    }
+}
+
+impl std::error::Error for Panicked {}
+
+/// Maps the elements on `num_threads` workers (at least one, and no more than there are elements)
+/// fed from a shared queue, and returns each result in its element's slot. Once a result
+/// satisfies `stop`, no more elements are handed out, and the slots of the ones left over stay
+/// `None`.
+fn map_indexed<T, U, F>(
+    input_vec: Vec<T>,
+    num_threads: usize,
+    f: F,
+    stop: fn(&U) -> bool,
+) -> Vec<Option<U>>
+where
+    F: Fn(T) -> U + Send + Sync + 'static,
+    T: Send + 'static,
+    U: Send + 'static,
+{
+    let len = input_vec.len();
+    let (input_tx, input_rx) = crossbeam_channel::unbounded();
+    for item in input_vec.into_iter().enumerate() {
+        input_tx
+            .send(item)
+            .expect("Sender error when queueing input");
+    }
+    // Once the queue is empty, workers find it disconnected and finish
+    drop(input_tx);
+
+    // Every worker calls the same closure, so it is shared rather than copied
+    let f = Arc::new(f);
+    let stopped = Arc::new(AtomicBool::new(false));
+    let (output_tx, output_rx) = crossbeam_channel::unbounded();
+    let mut threads = Vec::new();
+    for _ in 0..num_threads.max(1).min(len) {
+        let input_rx = input_rx.clone();
+        let output_tx = output_tx.clone();
+        let f = Arc::clone(&f);
+        let stopped = Arc::clone(&stopped);
+        threads.push(thread::spawn(move || {
+            while !stopped.load(Ordering::SeqCst) {
+                let (index, item) = match input_rx.recv() {
+                    Ok(next) => next,
+                    Err(_) => break,
+                };
+                let res = f(item);
+                if stop(&res) {
+                    stopped.store(true, Ordering::SeqCst);
+                }
+                output_tx
+                    .send((index, res))
+                    .expect("Sender error in worker threads");
+            }
+        }));
+    }
+    drop(output_tx);
+
+    // Results arrive in whatever order the workers finish them; each goes in its element's slot
+    let mut output_vec: Vec<Option<U>> = (0..len).map(|_| None).collect();
+    while let Ok((index, res)) = output_rx.recv() {
+        output_vec[index] = Some(res);
+    }
+
+    for handle in threads {
+        handle.join().expect("Panics when reaping child threads");
+    }
+    output_vec
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::{Duration, Instant};
+
+    #[test]
+    fn test_keeps_order() {
+        let input: Vec<u64> = (0..100).collect();
+        let expected: Vec<u64> = input.iter().map(|num| num * num).collect();
+        for num_threads in &[1, 3, 10, 200] {
+            assert_eq!(
+                parallel_map(input.clone(), *num_threads, |num| num * num),
+                expected
+            );
+        }
+    }
+
+    #[test]
+    fn test_zero_threads() {
+        assert_eq!(parallel_map(vec![1, 2, 3], 0, |num| num * 2), [2, 4, 6]);
+        assert_eq!(
+            parallel_map_catching(vec![1, 2], 0, |num| num + 1),
+            [Ok(2), Ok(3)]
+        );
+    }
+
+    #[test]
+    fn test_empty_input() {
+        assert!(parallel_map(Vec::<i32>::new(), 4, |num| num + 1).is_empty());
+    }
+
+    #[test]
+    fn test_non_copy_closure_and_output() {
+        // Neither the closure (which owns a String) nor its output (a struct without Default)
+        // has to be Copy
+        struct Greeting(String);
+        let greeting = String::from("hello");
+        let names = vec!["ann".to_string(), "bob".to_string(), "cy".to_string()];
+        let greetings = parallel_map(names, 2, move |name| {
+            Greeting(format!("{} {}", greeting, name))
+        });
+        let greetings: Vec<String> = greetings.into_iter().map(|g| g.0).collect();
+        assert_eq!(greetings, ["hello ann", "hello bob", "hello cy"]);
+    }
+
+    #[test]
+    fn test_slow_item_does_not_hold_up_others() {
+        // The first element waits for all the others to be done, which only happens if the other
+        // worker can take every one of them
+        let done = Arc::new(AtomicUsize::new(0));
+        let input: Vec<usize> = (0..20).collect();
+        let len = input.len();
+        let output = parallel_map(input, 2, move |num| {
+            if num == 0 {
+                let deadline = Instant::now() + Duration::from_secs(5);
+                while done.load(Ordering::SeqCst) < len - 1 && Instant::now() < deadline {
+                    thread::sleep(Duration::from_millis(1));
+                }
+                done.load(Ordering::SeqCst) == len - 1
+            } else {
+                done.fetch_add(1, Ordering::SeqCst);
+                true
+            }
+        });
+        assert!(output.iter().all(|&finished| finished));
+    }
+
+    #[test]
+    fn test_catching_panics() {
+        let output = parallel_map_catching((0..10).collect(), 3, |num: i32| {
+            if num % 4 == 1 {
+                panic!("{} is unlucky", num);
+            }
+            if num == 6 {
+                std::panic::panic_any(num);
+            }
+            num * 2
+        });
+        assert_eq!(output.len(), 10);
+        for (num, res) in output.into_iter().enumerate() {
+            match num {
+                1 | 5 | 9 => assert_eq!(
+                    res.unwrap_err().to_string(),
+                    format!("panicked: {} is unlucky", num)
+                ),
+                6 => assert_eq!(res, Err(Panicked { message: None })),
+                _ => assert_eq!(res, Ok(num as i32 * 2)),
+            }
+        }
+    }
+
+    #[test]
+    fn test_try_map() {
+        let strings = vec!["1", "22", "333"];
+        assert_eq!(
+            parallel_try_map(strings, 2, |s| s.parse::<u32>()),
+            Ok(vec![1, 22, 333])
+        );
+
+        let strings = vec!["1", "x", "3", "y"];
+        let parse = |s: &'static str| s.parse::<u32>().map_err(|_| s);
+        for num_threads in &[1, 4] {
+            assert_eq!(
+                parallel_try_map(strings.clone(), *num_threads, parse),
+                Err("x")
+            );
+        }
+    }
+
+    #[test]
+    fn test_try_map_zero_threads() {
+        assert_eq!(
+            parallel_try_map(vec![1, 2, 3], 0, |num| Ok::<_, ()>(num * 2)),
+            Ok(vec![2, 4, 6])
+        );
+        assert_eq!(
+            parallel_try_map(vec![1, 2, 3], 0, |num| if num == 2 {
+                Err(num)
+            } else {
+                Ok(num)
+            }),
+            Err(2)
+        );
+    }
+
+    #[test]
+    fn test_try_map_stops_at_error() {
+        // With one worker, elements are mapped in order, and none after the first failure
+        let calls = Arc::new(AtomicUsize::new(0));
+        let counted = Arc::clone(&calls);
+        let res = parallel_try_map((0..100).collect(), 1, move |num: u32| {
+            counted.fetch_add(1, Ordering::SeqCst);
+            if num >= 3 {
+                Err(num)
+            } else {
+                Ok(num)
+            }
+        });
+        assert_eq!(res, Err(3));
+        assert_eq!(calls.load(Ordering::SeqCst), 4);
+    }
+}
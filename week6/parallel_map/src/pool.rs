@@ -0,0 +1,271 @@
+use crossbeam_channel::{Receiver, Sender};
+use std::collections::BTreeMap;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::Arc;
+use std::thread;
+
+type Job = Box<dyn FnOnce() + Send + 'static>;
+
+/// How many elements `map_iter` keeps in the pool per worker, ahead of the ones it has yielded.
+const IN_FLIGHT_PER_THREAD: usize = 2;
+
+/// A fixed set of worker threads that jobs (and maps) are handed to, so that mapping in a loop
+/// doesn't start and join threads every time. Dropping the pool lets the workers finish the jobs
+/// already handed out, and joins them.
+pub struct ThreadPool {
+    sender: Option<Sender<Job>>,
+    workers: Vec<thread::JoinHandle<()>>,
+}
+
+impl ThreadPool {
+    /// Starts a pool of `num_threads` workers (at least one).
+    pub fn new(num_threads: usize) -> ThreadPool {
+        let (sender, receiver) = crossbeam_channel::unbounded::<Job>();
+        let workers = (0..num_threads.max(1))
+            .map(|_| {
+                let receiver = receiver.clone();
+                thread::spawn(move || {
+                    while let Ok(job) = receiver.recv() {
+                        // A panicking job mustn't take the worker with it; map_iter hands its
+                        // panics on to the caller itself
+                        let _ = panic::catch_unwind(AssertUnwindSafe(job));
+                    }
+                })
+            })
+            .collect();
+        ThreadPool {
+            sender: Some(sender),
+            workers,
+        }
+    }
+
+    pub fn num_threads(&self) -> usize {
+        self.workers.len()
+    }
+
+    /// Runs `job` on the next free worker. A panic in it is caught (after the panic hook has
+    /// reported it) so the worker stays available.
+    pub fn execute(&self, job: impl FnOnce() + Send + 'static) {
+        self.sender
+            .as_ref()
+            .expect("the sender is only taken when the pool is dropped")
+            .send(Box::new(job))
+            .expect("Sender error when queueing a job");
+    }
+
+    /// Applies `f` to every element of `iter` on the pool's workers, and returns the results in
+    /// the order of the elements they came from.
+    ///
+    /// # Panics
+    ///
+    /// If `f` panics, with the same payload, once the elements before it are done.
+    pub fn map<I, T, U, F>(&self, iter: I, f: F) -> Vec<U>
+    where
+        I: IntoIterator<Item = T>,
+        F: Fn(T) -> U + Send + Sync + 'static,
+        T: Send + 'static,
+        U: Send + 'static,
+    {
+        self.map_iter(iter, f).collect()
+    }
+
+    /// Like `map`, but yields the results one at a time as they are ready, in the order of the
+    /// elements. Elements are only taken from `iter` a few at a time, keeping the workers busy
+    /// without reading ahead of the consumer, so `iter` can be larger than would fit in memory
+    /// (or endless).
+    pub fn map_iter<I, T, U, F>(&self, iter: I, f: F) -> MapIter<'_, I::IntoIter, F, U>
+    where
+        I: IntoIterator<Item = T>,
+        F: Fn(T) -> U + Send + Sync + 'static,
+        T: Send + 'static,
+        U: Send + 'static,
+    {
+        let window = self.num_threads() * IN_FLIGHT_PER_THREAD;
+        // At most `window` results are ever waiting, so workers never block sending one
+        let (sender, receiver) = crossbeam_channel::bounded(window);
+        MapIter {
+            pool: self,
+            input: iter.into_iter(),
+            f: Arc::new(f),
+            window,
+            sender,
+            receiver,
+            submitted: 0,
+            next_index: 0,
+            done: BTreeMap::new(),
+        }
+    }
+}
+
+impl Drop for ThreadPool {
+    fn drop(&mut self) {
+        // Workers see the queue disconnect once it is empty
+        drop(self.sender.take());
+        for worker in self.workers.drain(..) {
+            worker.join().expect("Panics when reaping worker threads");
+        }
+    }
+}
+
+/// The results of `ThreadPool::map_iter`.
+pub struct MapIter<'a, I, F, U> {
+    pool: &'a ThreadPool,
+    input: I,
+    f: Arc<F>,
+    /// How many elements may be in the pool, or done but not yet yielded, at once.
+    window: usize,
+    sender: Sender<(usize, thread::Result<U>)>,
+    receiver: Receiver<(usize, thread::Result<U>)>,
+    /// How many elements have been handed to the pool.
+    submitted: usize,
+    /// The index of the element whose result is to be yielded next.
+    next_index: usize,
+    /// Results that came back ahead of an earlier element's.
+    done: BTreeMap<usize, thread::Result<U>>,
+}
+
+impl<I, T, U, F> MapIter<'_, I, F, U>
+where
+    I: Iterator<Item = T>,
+    F: Fn(T) -> U + Send + Sync + 'static,
+    T: Send + 'static,
+    U: Send + 'static,
+{
+    /// Hands elements to the pool until the window is full or the input runs out.
+    fn fill(&mut self) {
+        while self.submitted - self.next_index < self.window {
+            let item = match self.input.next() {
+                Some(item) => item,
+                None => break,
+            };
+            let index = self.submitted;
+            let f = Arc::clone(&self.f);
+            let sender = self.sender.clone();
+            self.pool.execute(move || {
+                let res = panic::catch_unwind(AssertUnwindSafe(|| f(item)));
+                // The MapIter may have been dropped, in which case nobody wants the result
+                let _ = sender.send((index, res));
+            });
+            self.submitted += 1;
+        }
+    }
+}
+
+impl<I, T, U, F> Iterator for MapIter<'_, I, F, U>
+where
+    I: Iterator<Item = T>,
+    F: Fn(T) -> U + Send + Sync + 'static,
+    T: Send + 'static,
+    U: Send + 'static,
+{
+    type Item = U;
+
+    fn next(&mut self) -> Option<U> {
+        self.fill();
+        if self.next_index == self.submitted {
+            return None;
+        }
+        let res = loop {
+            if let Some(res) = self.done.remove(&self.next_index) {
+                break res;
+            }
+            let (index, res) = self
+                .receiver
+                .recv()
+                .expect("every job handed out sends its result");
+            self.done.insert(index, res);
+        };
+        self.next_index += 1;
+        match res {
+            Ok(value) => Some(value),
+            Err(payload) => panic::resume_unwind(payload),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::collections::HashSet;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Mutex;
+    use std::time::Duration;
+
+    #[test]
+    fn test_map_reuses_workers() {
+        let pool = ThreadPool::new(3);
+        assert_eq!(pool.num_threads(), 3);
+        let seen = Arc::new(Mutex::new(HashSet::new()));
+        for round in 0..5u64 {
+            let seen = Arc::clone(&seen);
+            let output = pool.map(0..20u64, move |num| {
+                seen.lock().unwrap().insert(thread::current().id());
+                num * round
+            });
+            assert_eq!(output, (0..20).map(|num| num * round).collect::<Vec<_>>());
+        }
+        assert!(seen.lock().unwrap().len() <= 3);
+        assert!(pool.map(Vec::<u64>::new(), |num| num).is_empty());
+    }
+
+    #[test]
+    fn test_map_iter_keeps_order() {
+        // Later elements finish first, and still come out after the earlier ones
+        let pool = ThreadPool::new(4);
+        let output: Vec<u64> = pool
+            .map_iter(0..12u64, |num| {
+                thread::sleep(Duration::from_millis(12 - num));
+                num + 100
+            })
+            .collect();
+        assert_eq!(output, (100..112).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_map_iter_reads_input_lazily() {
+        let pool = ThreadPool::new(2);
+        let taken = Arc::new(AtomicUsize::new(0));
+        let counter = Arc::clone(&taken);
+        let input = (0u64..).inspect(move |_| {
+            counter.fetch_add(1, Ordering::SeqCst);
+        });
+        let first: Vec<u64> = pool.map_iter(input, |num| num * 2).take(5).collect();
+        assert_eq!(first, [0, 2, 4, 6, 8]);
+        // No more than a window's worth past what was yielded
+        assert!(taken.load(Ordering::SeqCst) <= 5 + 2 * IN_FLIGHT_PER_THREAD);
+    }
+
+    #[test]
+    fn test_panics_reach_the_caller() {
+        let pool = ThreadPool::new(2);
+        let res = panic::catch_unwind(AssertUnwindSafe(|| {
+            pool.map(0..10, |num| {
+                if num == 7 {
+                    panic!("seven");
+                }
+                num
+            })
+        }));
+        let payload = res.unwrap_err();
+        assert_eq!(payload.downcast_ref::<&str>(), Some(&"seven"));
+
+        // The workers survive it
+        assert_eq!(pool.map(0..4, |num| num + 1), [1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn test_execute() {
+        let pool = ThreadPool::new(2);
+        let (sender, receiver) = crossbeam_channel::unbounded();
+        for num in 0..4 {
+            let sender = sender.clone();
+            pool.execute(move || sender.send(num).unwrap());
+        }
+        pool.execute(|| panic!("a job that panics"));
+        drop(pool);
+        drop(sender);
+        let mut received: Vec<i32> = receiver.iter().collect();
+        received.sort_unstable();
+        assert_eq!(received, [0, 1, 2, 3]);
+    }
+}